@@ -1,3 +1,4 @@
+using System.Runtime.CompilerServices;
 using CustomLang.Types;
 
 namespace CustomLang {
@@ -17,6 +18,78 @@ public static class rmm_Builtin {
   public static void rmm_println(rmm_Int i) => rmm_println(i.rmm_toString());
   public static void rmm_println(rmm_Float f) => rmm_println(f.rmm_toString());
 
+  // ┌───────┐
+  // │ Input │
+  // └───────┘
+  // Reading past the end of the input yields an empty line
+  public static rmm_String rmm_readLine() => new(System.Console.ReadLine() ?? "");
+  public static rmm_Int rmm_readInt() => new(int.Parse(rmm_readLine().Inner.Trim()));
+  public static rmm_Float rmm_readFloat() => new(double.Parse(rmm_readLine().Inner.Trim()));
+
+  // ┌───────────┐
+  // │ Arguments │
+  // └───────────┘
+  // The first command-line argument is the path of the executable itself, which is skipped
+  public static rmm_Int rmm_argCount() => new(System.Environment.GetCommandLineArgs().Length - 1);
+  public static rmm_String rmm_arg(rmm_Int index) =>
+    new(System.Environment.GetCommandLineArgs()[index.Inner + 1]);
+
+  // ┌──────┐
+  // │ Exit │
+  // └──────┘
+  public static void rmm_exit(rmm_Int code) => System.Environment.Exit(code.Inner);
+
+  // ┌────────────┐
+  // │ Assertions │
+  // └────────────┘
+  // A failed assertion prints a message to stderr and exits with code 1. The caller's location
+  // comes from the #line directives, so it points at the source file
+  private static void AssertionFailed(string message, string file, int line) {
+    System.Console.Error.WriteLine($"Assertion failed at {file}:{line}: {message}");
+    System.Environment.Exit(1);
+  }
+
+  public static void rmm_assert(
+    rmm_Bool condition,
+    [CallerFilePath] string file = "",
+    [CallerLineNumber] int line = 0
+  ) {
+    if (!condition.Inner) AssertionFailed("condition is false", file, line);
+  }
+
+  public static void rmm_assertEq(
+    rmm_String left,
+    rmm_String right,
+    [CallerFilePath] string file = "",
+    [CallerLineNumber] int line = 0
+  ) {
+    if (left.Inner != right.Inner) AssertionFailed($"\"{left}\" != \"{right}\"", file, line);
+  }
+  public static void rmm_assertEq(
+    rmm_Bool left,
+    rmm_Bool right,
+    [CallerFilePath] string file = "",
+    [CallerLineNumber] int line = 0
+  ) {
+    if (left.Inner != right.Inner) AssertionFailed($"{left} != {right}", file, line);
+  }
+  public static void rmm_assertEq(
+    rmm_Int left,
+    rmm_Int right,
+    [CallerFilePath] string file = "",
+    [CallerLineNumber] int line = 0
+  ) {
+    if (left.Inner != right.Inner) AssertionFailed($"{left} != {right}", file, line);
+  }
+  public static void rmm_assertEq(
+    rmm_Float left,
+    rmm_Float right,
+    [CallerFilePath] string file = "",
+    [CallerLineNumber] int line = 0
+  ) {
+    if (left.Inner != right.Inner) AssertionFailed($"{left} != {right}", file, line);
+  }
+
   // ┌─────────┐
   // │ Parsing │
   // └─────────┘
@@ -7,6 +7,7 @@ public class rmm_String {
   public override string ToString() => value;
   public string Inner => value;
 
+  public rmm_String rmm_toString() => this;
   public rmm_Bool rmm_toBool() => new(value.Length != 0);
   public rmm_Int rmm_toInt() => new(int.Parse(value));
   public rmm_Float rmm_toFloat() => new(double.Parse(value));
@@ -42,8 +43,6 @@ public class rmm_Bool {
 
   public rmm_Bool rmm__bopEq(rmm_Bool other) => new(this.value == other.Inner);
   public rmm_Bool rmm__bopNe(rmm_Bool other) => new(this.value != other.Inner);
-  public rmm_Bool rmm__bopAnd(rmm_Bool other) => new(this.value && other.Inner);
-  public rmm_Bool rmm__bopOr(rmm_Bool other) => new(this.value || other.Inner);
 
   public rmm_Bool rmm__uopNot() => new(!this.value);
 }
@@ -59,10 +58,34 @@ public class rmm_Int {
   public rmm_Bool rmm_toBool() => new(value != 0);
   public rmm_Float rmm_toFloat() => new((double)value);
 
-  public rmm_Int rmm__bopAdd(rmm_Int other) => new(this.value + other.Inner);
-  public rmm_Int rmm__bopSub(rmm_Int other) => new(this.value - other.Inner);
-  public rmm_Int rmm__bopMul(rmm_Int other) => new(this.value * other.Inner);
-  public rmm_Int rmm__bopDiv(rmm_Int other) => new(this.value / other.Inner);
+  // The overflow policy is selected by the compiler, wrapping around by default
+  private static int Overflow(long result) {
+#if OVERFLOW_SATURATE
+    return (int)Math.Clamp(result, int.MinValue, int.MaxValue);
+#elif OVERFLOW_ERROR
+    return checked((int)result);
+#else
+    return unchecked((int)result);
+#endif
+  }
+
+  public rmm_Int rmm__bopAdd(rmm_Int other) => new(Overflow((long)this.value + other.Inner));
+  public rmm_Int rmm__bopSub(rmm_Int other) => new(Overflow((long)this.value - other.Inner));
+  public rmm_Int rmm__bopMul(rmm_Int other) => new(Overflow((long)this.value * other.Inner));
+  // int.MinValue / -1 is the only quotient that overflows, and int.MinValue % -1 throws in C#
+  // although its result is 0, so both are computed on longs as well
+  public rmm_Int rmm__bopDiv(rmm_Int other) => new(Overflow((long)this.value / other.Inner));
+  public rmm_Int rmm__bopMod(rmm_Int other) => new(Overflow((long)this.value % other.Inner));
+  public rmm_Int rmm__bopPow(rmm_Int other) {
+    if (other.Inner < 0) throw new ArithmeticException("Negative exponent in integer power");
+    // Exponentiation by squaring, where the base is only squared if it is used again
+    int result = 1, power = this.value;
+    for (int exponent = other.Inner; exponent > 0; exponent >>= 1) {
+      if ((exponent & 1) == 1) result = Overflow((long)result * power);
+      if (exponent > 1) power = Overflow((long)power * power);
+    }
+    return new(result);
+  }
   public rmm_Bool rmm__bopEq(rmm_Int other) => new(this.value == other.Inner);
   public rmm_Bool rmm__bopNe(rmm_Int other) => new(this.value != other.Inner);
   public rmm_Bool rmm__bopLt(rmm_Int other) => new(this.value < other.Inner);
@@ -74,12 +97,16 @@ public class rmm_Int {
   public rmm_Float rmm__bopSub(rmm_Float other) => new(this.value - other.Inner);
   public rmm_Float rmm__bopMul(rmm_Float other) => new(this.value * other.Inner);
   public rmm_Float rmm__bopDiv(rmm_Float other) => new(this.value / other.Inner);
+  public rmm_Float rmm__bopMod(rmm_Float other) => new(this.value % other.Inner);
+  public rmm_Float rmm__bopPow(rmm_Float other) => new(Math.Pow(this.value, other.Inner));
   public rmm_Bool rmm__bopEq(rmm_Float other) => new(this.value == other.Inner);
   public rmm_Bool rmm__bopNe(rmm_Float other) => new(this.value != other.Inner);
   public rmm_Bool rmm__bopLt(rmm_Float other) => new(this.value < other.Inner);
   public rmm_Bool rmm__bopGt(rmm_Float other) => new(this.value > other.Inner);
   public rmm_Bool rmm__bopLe(rmm_Float other) => new(this.value <= other.Inner);
   public rmm_Bool rmm__bopGe(rmm_Float other) => new(this.value >= other.Inner);
+
+  public rmm_Int rmm__uopNeg() => new(Overflow(-(long)this.value));
 }
 
 public class rmm_Float {
@@ -97,6 +124,8 @@ public class rmm_Float {
   public rmm_Float rmm__bopSub(rmm_Float other) => new(this.value - other.Inner);
   public rmm_Float rmm__bopMul(rmm_Float other) => new(this.value * other.Inner);
   public rmm_Float rmm__bopDiv(rmm_Float other) => new(this.value / other.Inner);
+  public rmm_Float rmm__bopMod(rmm_Float other) => new(this.value % other.Inner);
+  public rmm_Float rmm__bopPow(rmm_Float other) => new(Math.Pow(this.value, other.Inner));
   public rmm_Bool rmm__bopEq(rmm_Float other) => new(this.value == other.Inner);
   public rmm_Bool rmm__bopNe(rmm_Float other) => new(this.value != other.Inner);
   public rmm_Bool rmm__bopLt(rmm_Float other) => new(this.value < other.Inner);
@@ -108,12 +137,16 @@ public class rmm_Float {
   public rmm_Float rmm__bopSub(rmm_Int other) => new(this.value - other.Inner);
   public rmm_Float rmm__bopMul(rmm_Int other) => new(this.value * other.Inner);
   public rmm_Float rmm__bopDiv(rmm_Int other) => new(this.value / other.Inner);
+  public rmm_Float rmm__bopMod(rmm_Int other) => new(this.value % other.Inner);
+  public rmm_Float rmm__bopPow(rmm_Int other) => new(Math.Pow(this.value, other.Inner));
   public rmm_Bool rmm__bopEq(rmm_Int other) => new(this.value == other.Inner);
   public rmm_Bool rmm__bopNe(rmm_Int other) => new(this.value != other.Inner);
   public rmm_Bool rmm__bopLt(rmm_Int other) => new(this.value < other.Inner);
   public rmm_Bool rmm__bopGt(rmm_Int other) => new(this.value > other.Inner);
   public rmm_Bool rmm__bopLe(rmm_Int other) => new(this.value <= other.Inner);
   public rmm_Bool rmm__bopGe(rmm_Int other) => new(this.value >= other.Inner);
+
+  public rmm_Float rmm__uopNeg() => new(-this.value);
 }
 
 }
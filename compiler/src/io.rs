@@ -1,19 +1,30 @@
 //! IO Operations for the Compiler
 
 use std::{
-    fs, io,
-    path::PathBuf,
+    fs,
+    path::{Path, PathBuf},
     process::{Command, ExitStatus},
 };
 
-const TEMP_DIR: &str = "__tmp__cs_runtime";
+use crate::GeneratedProject;
 
-static BUILTIN_FILE: &[u8] = include_bytes!("../cs_runtime/Builtin.cs");
-static CSPROJ_FILE: &[u8] = include_bytes!("../cs_runtime/cs_runtime.csproj");
-static TYPES_FILE: &[u8] = include_bytes!("../cs_runtime/Types.cs");
+/// The directory inside the build directory the executable is published to.
+const PUBLISH_DIR: &str = "publish";
 
+pub static BUILTIN_FILE: &str = include_str!("../cs_runtime/Builtin.cs");
+static CSPROJ_FILE: &str = include_str!("../cs_runtime/cs_runtime.csproj");
+pub static TYPES_FILE: &str = include_str!("../cs_runtime/Types.cs");
+
+/// The runtime files every program is built with, by their name in the project.
+pub static RUNTIME_FILES: &[(&str, &str)] = &[
+    ("Builtins.cs", BUILTIN_FILE),
+    ("Types.cs", TYPES_FILE),
+    ("cs_runtime.csproj", CSPROJ_FILE),
+];
+
+/// The .NET runtime identifier of the host platform.
 #[rustfmt::skip]
-const DOTNET_RID: &str = {
+pub const DOTNET_RID: &str = {
     #[cfg(all(target_os = "linux", target_arch = "x86_64"))] { "linux-x64" }
     #[cfg(all(target_os = "linux", target_arch = "aarch64"))] { "linux-arm64" }
     #[cfg(all(target_os = "windows", target_arch = "x86_64"))] { "win-x64" }
@@ -22,58 +33,50 @@ const DOTNET_RID: &str = {
     #[cfg(all(target_os = "macos", target_arch = "aarch64"))] { "osx-arm64" }
 };
 
-fn get_cwd() -> io::Result<PathBuf> {
-    std::env::current_dir()
-}
-
-pub fn copy_runtime() {
-    let cwd: PathBuf = get_cwd().expect("Failed to get current working directory");
-    let dest: PathBuf = cwd.join(TEMP_DIR);
-    let mut target: PathBuf;
-
-    fs::create_dir_all(&dest).expect("Failed to create runtime destination directory");
-
-    target = dest.join("Builtins.cs");
-    if !target.exists() {
-        fs::write(&target, BUILTIN_FILE).expect("Failed to write runtime file");
-    }
-
-    target = dest.join("Types.cs");
-    if !target.exists() {
-        fs::write(&target, TYPES_FILE).expect("Failed to write runtime file");
-    }
+pub fn write_project(build_dir: &Path, project: &GeneratedProject) {
+    fs::create_dir_all(build_dir).expect("Failed to create runtime destination directory");
 
-    target = dest.join("cs_runtime.csproj");
-    if !target.exists() {
-        fs::write(&target, CSPROJ_FILE).expect("Failed to write runtime file");
+    for file in &project.files {
+        fs::write(build_dir.join(file.name), &file.contents).expect("Failed to write project file");
     }
 }
 
-pub fn write_file(cs_code: &str) {
-    let cwd: PathBuf = get_cwd().expect("Failed to get current working directory");
-    let runtime_dir: PathBuf = cwd.join(TEMP_DIR);
-    let file_path: PathBuf = runtime_dir.join("Program.cs");
+/// Writes a `Directory.Build.props` that sets the overflow policy, which dotnet imports into the
+/// project automatically.
+pub fn write_build_props(build_dir: &Path, overflow_define: &str) {
+    let props: String = format!(
+        "<Project>\n  <PropertyGroup>\n    <IntegerOverflow>{overflow_define}</IntegerOverflow>\n  \
+         </PropertyGroup>\n</Project>\n"
+    );
 
-    fs::write(file_path, cs_code).expect("Failed to write C# code to file");
+    fs::write(build_dir.join("Directory.Build.props"), props)
+        .expect("Failed to write build properties");
 }
 
-pub fn call_compiler() -> bool {
-    let cwd: PathBuf = get_cwd().expect("Failed to get current working directory");
-    let runtime_dir: PathBuf = cwd.join(TEMP_DIR);
+pub fn call_compiler(build_dir: &Path, target: &str, overflow_define: &str, debug: bool) -> bool {
+    let overflow_property: String = format!("/p:IntegerOverflow={overflow_define}");
+
+    // Debug builds embed their symbols into the executable, so there is no extra file to move
+    let (configuration, debug_type, debug_symbols): (&str, &str, &str) = if debug {
+        ("Debug", "/p:DebugType=embedded", "/p:DebugSymbols=true")
+    } else {
+        ("Release", "/p:DebugType=None", "/p:DebugSymbols=false")
+    };
 
     #[rustfmt::skip]
     let status: ExitStatus = Command::new("dotnet")
         .args([
             "publish",
-            "-c", "Release",
-            "-r", DOTNET_RID,
+            "-c", configuration,
+            "-r", target,
             "--self-contained", "true",
             "/p:PublishSingleFile=true",
-            "/p:DebugType=None",
-            "/p:DebugSymbols=false",
-            "-o", ".."
+            debug_type,
+            debug_symbols,
+            &overflow_property,
+            "-o", PUBLISH_DIR
         ])
-        .current_dir(&runtime_dir)
+        .current_dir(build_dir)
         .status()
         .expect("Failed to execute dotnet publish command");
 
@@ -84,23 +87,21 @@ pub fn call_compiler() -> bool {
     true
 }
 
-pub fn cleanup_temp_files() {
-    let cwd: PathBuf = get_cwd().expect("Failed to get current working directory");
-    let runtime_dir: PathBuf = cwd.join(TEMP_DIR);
-
-    fs::remove_dir_all(&runtime_dir).expect("Failed to remove temporary runtime directory");
+pub fn cleanup_temp_files(build_dir: &Path) {
+    fs::remove_dir_all(build_dir).expect("Failed to remove temporary runtime directory");
 }
 
-pub fn move_executable(output_file: &str) {
-    let cwd: PathBuf = get_cwd().expect("Failed to get current working directory");
-    #[rustfmt::skip]
-    let temp_exe: PathBuf = cwd.join({
-        #[cfg(target_os = "windows")] { "__tmp__customlang.exe" }
-        #[cfg(not(target_os = "windows"))] { "__tmp__customlang" }
-    });
-    let dest_exe: PathBuf = cwd.join(output_file);
-
-    if let Some(dest_dir) = dest_exe.parent() {
+pub fn move_executable(build_dir: &Path, target: &str, output_file: &Path) {
+    // The name depends on the platform the executable was built for, not the host
+    let temp_exe: PathBuf = build_dir
+        .join(PUBLISH_DIR)
+        .join(if target.starts_with("win") {
+            "__tmp__customlang.exe"
+        } else {
+            "__tmp__customlang"
+        });
+
+    if let Some(dest_dir) = output_file.parent() {
         if fs::create_dir_all(dest_dir).is_err() {
             eprintln!("Failed to create output directory, skipping...");
         }
@@ -108,5 +109,5 @@ pub fn move_executable(output_file: &str) {
         eprintln!("Failed to get output directory, skipping...");
     }
 
-    fs::rename(temp_exe, dest_exe).expect("Failed to move executable to output file");
+    fs::rename(temp_exe, output_file).expect("Failed to move executable to output file");
 }
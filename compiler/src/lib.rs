@@ -2,32 +2,250 @@
 
 mod io;
 
+use std::path::{Path, PathBuf};
+
+/// What integer arithmetic does when its result doesn't fit into the runtime's 32-bit integers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntegerOverflow {
+    /// The result wraps around, e.g. the maximum integer plus one is the minimum integer.
+    #[default]
+    Wrap,
+    /// The result is clamped to the minimum or maximum integer.
+    Saturate,
+    /// The operation throws an error, which can be caught with try/catch.
+    Error,
+}
+
+impl IntegerOverflow {
+    /// Parses an overflow policy from its name, which is one of `wrap`, `saturate` or `error`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "wrap" => Some(Self::Wrap),
+            "saturate" => Some(Self::Saturate),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+
+    /// The preprocessor symbol the runtime checks to select the policy.
+    const fn define(self) -> &'static str {
+        match self {
+            Self::Wrap => "OVERFLOW_WRAP",
+            Self::Saturate => "OVERFLOW_SATURATE",
+            Self::Error => "OVERFLOW_ERROR",
+        }
+    }
+}
+
+/// Options for compiling a program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileOptions {
+    /// The path of the executable, `output` by default.
+    pub output_file: PathBuf,
+    /// The directory the C# project is generated and built in, `__tmp__cs_runtime` by default.
+    /// Builds running in parallel need different directories.
+    pub build_dir: PathBuf,
+    /// The .NET runtime identifier of the platform to build for, e.g. `linux-arm64` or
+    /// `linux-musl-x64`. The host platform by default.
+    pub target: String,
+    /// What integer arithmetic in the compiled program does on overflow.
+    pub overflow: IntegerOverflow,
+    /// Whether to build an unoptimized executable with debug symbols, keeping the build directory
+    /// for inspection.
+    pub debug: bool,
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        #[rustfmt::skip]
+        let output_file: &str = {
+            #[cfg(target_os = "windows")] { "output.exe" }
+            #[cfg(not(target_os = "windows"))] { "output" }
+        };
+
+        Self {
+            output_file: PathBuf::from(output_file),
+            build_dir: PathBuf::from("__tmp__cs_runtime"),
+            target: String::from(io::DOTNET_RID),
+            overflow: IntegerOverflow::default(),
+            debug: false,
+        }
+    }
+}
+
+/// A file of a generated C# project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectFile {
+    /// The name of the file inside the project directory, e.g. `Program.cs`.
+    pub name: &'static str,
+    /// The contents of the file.
+    pub contents: String,
+}
+
+/// The C# project a program is built from, kept in memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedProject {
+    /// The files of the project: the transpiled program in `Program.cs`, the runtime and the
+    /// project file.
+    pub files: Vec<ProjectFile>,
+}
+
+impl GeneratedProject {
+    /// Returns the file with the given name, if the project contains it.
+    #[must_use]
+    pub fn file(&self, name: &str) -> Option<&ProjectFile> {
+        self.files.iter().find(|file| file.name == name)
+    }
+}
+
 /// The compiler for the transpiled code
 pub struct Compiler;
 
 impl Compiler {
     /// Compiles the given C# code into an executable binary.
     ///
+    /// Relative paths in the options are relative to the current working directory.
+    ///
     /// Arguments:
     /// - `cs_code`: The C# code to compile as a string slice.
-    pub fn compile(cs_code: &str, output_file: Option<String>) {
-        io::copy_runtime();
-        io::write_file(cs_code);
-        if !io::call_compiler() {
+    /// - `options`: Where to build and put the executable, and how to build it.
+    pub fn compile(cs_code: &str, options: &CompileOptions) {
+        let build_dir: &Path = &options.build_dir;
+
+        io::write_project(build_dir, &Self::generate(cs_code));
+        if !io::call_compiler(
+            build_dir,
+            &options.target,
+            options.overflow.define(),
+            options.debug,
+        ) {
             #[cfg(not(debug_assertions))]
-            {
+            if !options.debug {
                 println!();
                 eprintln!("Dotnet publish command failed, cleaning up temporary files...");
-                io::cleanup_temp_files();
+                io::cleanup_temp_files(build_dir);
             }
             std::process::exit(1);
         }
-        io::cleanup_temp_files();
 
-        #[rustfmt::skip]
-        io::move_executable(&output_file.unwrap_or_else( || {
-            #[cfg(target_os = "windows")] { "output.exe".to_string() }
-            #[cfg(not(target_os = "windows"))] { "output".to_string() }
-        }));
+        io::move_executable(build_dir, &options.target, &options.output_file);
+
+        if options.debug {
+            eprintln!(
+                "Keeping the generated C# project in {}",
+                build_dir.display()
+            );
+        } else {
+            io::cleanup_temp_files(build_dir);
+        }
+    }
+
+    /// Generates the C# project for the given C# code without touching the filesystem. The
+    /// integer overflow policy is not part of the project, it is passed to the build.
+    ///
+    /// Arguments:
+    /// - `cs_code`: The C# code from the transpiler.
+    #[must_use]
+    pub fn generate(cs_code: &str) -> GeneratedProject {
+        let mut files: Vec<ProjectFile> = vec![ProjectFile {
+            name: "Program.cs",
+            contents: cs_code.to_string(),
+        }];
+        files.extend(
+            io::RUNTIME_FILES
+                .iter()
+                .map(|(name, contents)| ProjectFile {
+                    name,
+                    contents: (*contents).to_string(),
+                }),
+        );
+
+        GeneratedProject { files }
+    }
+
+    /// Writes the C# project for the given C# code into the given directory, creating it if
+    /// needed. Unlike the project [`Compiler::compile`] builds, it contains the overflow policy,
+    /// so `dotnet build` and `dotnet run` work in it without any extra properties.
+    ///
+    /// Arguments:
+    /// - `cs_code`: The C# code from the transpiler.
+    /// - `dir`: The directory to write the project to.
+    /// - `overflow`: What integer arithmetic in the compiled program does on overflow.
+    pub fn write_project(cs_code: &str, dir: &Path, overflow: IntegerOverflow) {
+        io::write_project(dir, &Self::generate(cs_code));
+        io::write_build_props(dir, overflow.define());
+    }
+
+    /// Combines the given C# code and the runtime into a single self-contained C# file, which
+    /// any C# build with top-level statements enabled can compile without the generated project.
+    ///
+    /// Arguments:
+    /// - `cs_code`: The C# code from the transpiler.
+    /// - `overflow`: What integer arithmetic in the compiled program does on overflow.
+    #[must_use]
+    pub fn amalgamate(cs_code: &str, overflow: IntegerOverflow) -> String {
+        // Directives have to come first, and usings before the top-level statements of the
+        // program, which in turn have to come before the runtime's namespaces. The runtime must
+        // not be mapped to the source file by the program's last #line directive
+        let mut usings: String = String::from("using System;\n");
+        let mut runtime: String = String::new();
+
+        for file in [io::TYPES_FILE, io::BUILTIN_FILE] {
+            for line in file.lines() {
+                let target: &mut String = if line.starts_with("using ") {
+                    &mut usings
+                } else {
+                    &mut runtime
+                };
+                target.push_str(line);
+                target.push('\n');
+            }
+        }
+
+        format!(
+            "#define {}\n{usings}\n{cs_code}\n#line default\n\n// RUNTIME\n\n{runtime}",
+            overflow.define()
+        )
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod compiler_tests {
+    use super::*;
+
+    #[test]
+    fn generated_project_files() {
+        let project: GeneratedProject = Compiler::generate("return 0;\n");
+        let names: Vec<&str> = project.files.iter().map(|file| file.name).collect();
+
+        assert_eq!(
+            names,
+            ["Program.cs", "Builtins.cs", "Types.cs", "cs_runtime.csproj"]
+        );
+        assert_eq!(project.file("Program.cs").unwrap().contents, "return 0;\n");
+        assert!(
+            project
+                .file("Types.cs")
+                .unwrap()
+                .contents
+                .contains("public class rmm_Int")
+        );
+        assert!(
+            project
+                .file("Builtins.cs")
+                .unwrap()
+                .contents
+                .contains("public static class rmm_Builtin")
+        );
+        assert!(
+            project
+                .file("cs_runtime.csproj")
+                .unwrap()
+                .contents
+                .contains("$(IntegerOverflow)")
+        );
+        assert_eq!(project.file("Main.cs"), None);
     }
 }
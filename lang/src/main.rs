@@ -1,16 +1,24 @@
 #![allow(missing_docs)]
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use compiler::Compiler;
+use compiler::{CompileOptions, Compiler, IntegerOverflow};
 use lexer::{Lexer, types::Token};
-use parser::{Parser, types::Program};
-use semantics::SemanticAnalyzer;
-use transpiler::Transpiler;
+use parser::{Parser, errors::ParseError, types::Program};
+use semantics::{
+    AnalyzerOptions, SemanticAnalyzer, constants,
+    errors::{Diagnostic, Severity},
+    lints::LintConfig,
+    resolve::{self, Symbol},
+    types::NumericCoercion,
+};
+use transpiler::{Transpiler, errors::TranspileError};
 
 const LANGUAGE_EXTENSION: &str = "cl";
 
-const USAGE: &str = r"
+const PROJECT_CONFIG: &str = "lang.toml";
+
+const USAGE: &str = r#"
 
 USAGE:
   lang <source-file> [OPTIONS]
@@ -31,8 +39,37 @@ OPTIONS:
                              2: Parsing / AST Generation + Semantic Analysis
                              3: Transpilation
   -p  --pretty            Pretty-print the output when using -s/--step with a value
-                           of either 1 or 2. Not allowed otherwised
-";
+                           of either 1 or 2, or when using --emit ast. Not allowed otherwised
+  --emit ast              Stop after parsing and output the AST as JSON to stdout. Cannot
+                           be used with -o or -s/--step.
+  --emit cs               Stop after transpiling and output a single C# file containing the
+                           program and the runtime to stdout. Cannot be used with -o or
+                           -s/--step.
+  --project <directory>   Stop after transpiling and write a complete C# project with the
+                           program and the runtime to the given directory, which can be
+                           built and run with dotnet. Cannot be used with -o, -s/--step or
+                           --emit.
+  --warnings-as-errors    Report warnings from semantic analysis as errors and stop the
+                           compilation if there are any.
+  --strict-numbers        Reject operators that mix int and float operands instead of
+                           converting the int to a float.
+  --overflow <policy>     Specify what integer arithmetic in the compiled program does
+                           when its result doesn't fit into 32 bits.
+                             wrap: Wrap around (default)
+                             saturate: Clamp to the minimum or maximum integer
+                             error: Throw an error, which try/catch can catch
+  --target <rid>          Build the executable for the platform with the given .NET runtime
+                           identifier instead of the host, e.g. linux-arm64, linux-musl-x64
+                           or win-x64. Cannot be used with -s/--step, --emit or --project.
+  --debug                 Build an unoptimized executable with embedded debug symbols and
+                           keep the generated C# project in __tmp__cs_runtime. Cannot be used
+                           with -s/--step, --emit or --project.
+
+CONFIGURATION:
+  A lang.toml file next to the source file can set the level of individual lints to
+  allow, warn or deny in its [lints] section, e.g. unused_variable = "deny".
+  Lints: unreachable_code, unused_variable, unused_function, dead_store
+"#;
 
 #[allow(clippy::too_many_lines)]
 fn main() {
@@ -98,10 +135,98 @@ fn main() {
             step_parsed
         });
 
+    let mut emit_ast: bool = false;
+    let mut emit_cs: bool = false;
+
+    if let Some(index) = args.iter().position(|x| x == "--emit") {
+        if index + 1 >= args.len() {
+            eprint!("Missing value for --emit option. {USAGE}");
+            std::process::exit(1);
+        }
+
+        match args[index + 1].as_str() {
+            "ast" => emit_ast = true,
+            "cs" => emit_cs = true,
+            _ => {
+                eprint!("Invalid value for --emit. Must be 'ast' or 'cs'. {USAGE}");
+                std::process::exit(1);
+            }
+        }
+
+        if output_filename.is_some() || step != 0 {
+            eprint!("--emit cannot be used with -o or -s/--step. {USAGE}");
+            std::process::exit(1);
+        }
+
+        args.drain(index..=index + 1);
+    }
+
+    let mut project_dir: Option<PathBuf> = None;
+
+    if let Some(index) = args.iter().position(|x| x == "--project") {
+        if index + 1 >= args.len() {
+            eprint!("Missing value for --project option. {USAGE}");
+            std::process::exit(1);
+        }
+
+        if output_filename.is_some() || step != 0 || emit_ast || emit_cs {
+            eprint!("--project cannot be used with -o, -s/--step or --emit. {USAGE}");
+            std::process::exit(1);
+        }
+
+        project_dir = Some(PathBuf::from(&args[index + 1]));
+        args.drain(index..=index + 1);
+    }
+
+    let mut overflow: IntegerOverflow = IntegerOverflow::default();
+
+    if let Some(index) = args.iter().position(|x| x == "--overflow") {
+        if index + 1 >= args.len() {
+            eprint!("Missing value for --overflow option. {USAGE}");
+            std::process::exit(1);
+        }
+
+        overflow = IntegerOverflow::from_name(&args[index + 1]).unwrap_or_else(|| {
+            eprint!("Invalid value for --overflow. Must be 'wrap', 'saturate' or 'error'. {USAGE}");
+            std::process::exit(1);
+        });
+        args.drain(index..=index + 1);
+    }
+
+    let mut target: Option<String> = None;
+
+    if let Some(index) = args.iter().position(|x| x == "--target") {
+        if index + 1 >= args.len() {
+            eprint!("Missing value for --target option. {USAGE}");
+            std::process::exit(1);
+        }
+
+        target = Some(args[index + 1].clone());
+        args.drain(index..=index + 1);
+    }
+
     let pretty: bool = args.iter().any(|x| x == "-p" || x == "--pretty");
+    let warnings_as_errors: bool = args.iter().any(|x| x == "--warnings-as-errors");
+    let debug: bool = args.iter().any(|x| x == "--debug");
+    let numeric_coercion: NumericCoercion = if args.iter().any(|x| x == "--strict-numbers") {
+        NumericCoercion::Strict
+    } else {
+        NumericCoercion::Coerce
+    };
 
-    if pretty && !(step == 1 || step == 2) {
-        eprint!("-p/--pretty can only be used with -s/--step when step is 1 or 2. {USAGE}");
+    if pretty && !(step == 1 || step == 2 || emit_ast) {
+        eprint!(
+            "-p/--pretty can only be used with --emit or with -s/--step when step is 1 or 2. \
+             {USAGE}"
+        );
+        std::process::exit(1);
+    }
+
+    if (target.is_some() || debug) && (step != 0 || emit_ast || emit_cs || project_dir.is_some()) {
+        eprint!(
+            "--target and --debug only apply when building an executable and cannot be used with \
+             -s/--step, --emit or --project. {USAGE}"
+        );
         std::process::exit(1);
     }
 
@@ -138,20 +263,76 @@ fn main() {
         std::process::exit(0);
     }
 
-    let program: Result<Program, String> = Parser::parse(tokens);
-    let program: Program = match program {
+    let program: Result<Program, ParseError> = Parser::parse(tokens);
+    let mut program: Program = match program {
         Ok(p) => p,
         Err(e) => {
-            eprintln!("Parser error: {e}");
+            e.print();
             std::process::exit(1);
         }
     };
 
-    SemanticAnalyzer::analyze(program.clone()).unwrap_or_else(|e| {
-        e.print();
+    if emit_ast {
+        let json: Result<String, serde_json::Error> = if pretty {
+            serde_json::to_string_pretty(&program)
+        } else {
+            serde_json::to_string(&program)
+        };
+
+        match json {
+            Ok(json) => println!("{json}"),
+            Err(e) => {
+                eprintln!("Error serializing AST: {e}");
+                std::process::exit(1);
+            }
+        }
+        std::process::exit(0);
+    }
+
+    let config_path: std::path::PathBuf = filepath.with_file_name(PROJECT_CONFIG);
+    let lints: LintConfig = if config_path.exists() {
+        std::fs::read_to_string(&config_path)
+            .map_err(|e| e.to_string())
+            .and_then(|config| LintConfig::from_config(&config))
+            .unwrap_or_else(|e| {
+                eprintln!("Error reading {}: {e}", config_path.display());
+                std::process::exit(1);
+            })
+    } else {
+        LintConfig::default()
+    };
+
+    let diagnostics: Vec<Diagnostic> = SemanticAnalyzer::analyze_with_options(
+        program.clone(),
+        &AnalyzerOptions {
+            lints,
+            numeric_coercion,
+        },
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("{}", e.error_message_with_source(&source_code));
+        if let Some(symbol) = resolve::resolve(&program).reference_at((e.line, e.column)) {
+            print_defined_here(symbol, &source_code);
+        }
         std::process::exit(1);
     });
 
+    let mut failed: bool = false;
+    for mut diagnostic in diagnostics {
+        if warnings_as_errors && diagnostic.severity == Severity::Warning {
+            diagnostic.severity = Severity::Error;
+        }
+        failed |= diagnostic.severity == Severity::Error;
+        eprintln!(
+            "{}",
+            diagnostic.diagnostic_message_with_source(&source_code)
+        );
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+
     if step == 2 {
         if pretty {
             print!("{program:#?}");
@@ -161,12 +342,20 @@ fn main() {
         std::process::exit(0);
     }
 
-    let transpiled_code: Result<String, String> = Transpiler::transpile(program);
+    constants::fold(&mut program);
+
+    // Only the C# that gets built maps back to the source file, printed C# stays readable
+    let transpiled_code: Result<String, TranspileError> = if step == 3 {
+        Transpiler::transpile(program)
+    } else {
+        let source_path: PathBuf = filepath.canonicalize().unwrap_or_else(|_| filepath.into());
+        Transpiler::transpile_with_line_directives(program, &source_path.to_string_lossy())
+    };
 
     let transpiled_code: String = match transpiled_code {
         Ok(c) => c,
         Err(e) => {
-            eprintln!("Transpiler error: {e}");
+            eprintln!("{}", e.error_message_with_source(&source_code));
             std::process::exit(1);
         }
     };
@@ -176,5 +365,38 @@ fn main() {
         std::process::exit(0);
     }
 
-    Compiler::compile(&transpiled_code, output_filename);
+    if emit_cs {
+        print!("{}", Compiler::amalgamate(&transpiled_code, overflow));
+        std::process::exit(0);
+    }
+
+    if let Some(project_dir) = project_dir {
+        Compiler::write_project(&transpiled_code, &project_dir, overflow);
+        eprintln!("Wrote the C# project to {}", project_dir.display());
+        std::process::exit(0);
+    }
+
+    let mut options: CompileOptions = CompileOptions {
+        overflow,
+        debug,
+        ..CompileOptions::default()
+    };
+    if let Some(output_filename) = output_filename {
+        options.output_file = PathBuf::from(output_filename);
+    }
+    if let Some(target) = target {
+        options.target = target;
+    }
+
+    Compiler::compile(&transpiled_code, &options);
+}
+
+/// Prints a note pointing at the declaration of the given symbol.
+fn print_defined_here(symbol: &Symbol, source_code: &str) {
+    let note: Diagnostic = Diagnostic::note(
+        "DefinedHere",
+        format!("'{}' is defined here", symbol.name),
+        symbol.declared_at,
+    );
+    eprintln!("{}", note.diagnostic_message_with_source(source_code));
 }
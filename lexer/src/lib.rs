@@ -31,41 +31,49 @@ impl Lexer {
 
         let mut tokens: Vec<Token> = vec![];
 
-        'lex: while lexer.index < lexer.source.len() {
-            let current_char: char = lexer.source[lexer.index];
+        let end: usize = lexer.source.len();
+        lexer.lex_until(end, &mut tokens)?;
+
+        tokens.push(Token::single(
+            TokenKind::EndOfFile,
+            lexer.line,
+            lexer.column,
+        ));
+
+        Ok(tokens)
+    }
+
+    /// Tokenizes the source code from the current index up to (excluding) index `end`.
+    fn lex_until(&mut self, end: usize, tokens: &mut Vec<Token>) -> Result<(), String> {
+        'lex: while self.index < end {
+            let current_char: char = self.source[self.index];
 
             if current_char.is_whitespace() {
                 if current_char == '\n' {
-                    lexer.line += 1;
-                    lexer.column = 1;
+                    self.line += 1;
+                    self.column = 1;
                 } else {
-                    lexer.column += 1;
+                    self.column += 1;
                 }
-                lexer.index += 1;
+                self.index += 1;
                 continue 'lex;
             }
 
-            if lexer.multiple_char_token(&mut tokens)? {
+            if self.multiple_char_token(tokens)? {
                 continue 'lex;
             }
 
-            if lexer.single_char_token(&mut tokens) {
+            if self.single_char_token(tokens) {
                 continue 'lex;
             }
 
             return Err(format!(
                 "Unknown character '{}' at {}:{}",
-                current_char, lexer.line, lexer.column
+                current_char, self.line, self.column
             ));
         }
 
-        tokens.push(Token::single(
-            TokenKind::EndOfFile,
-            lexer.line,
-            lexer.column,
-        ));
-
-        Ok(tokens)
+        Ok(())
     }
 
     fn multiple_char_token(&mut self, tokens: &mut Vec<Token>) -> Result<bool, String> {
@@ -97,6 +105,9 @@ impl Lexer {
                 "!=" => Some(TokenKind::NotEquals),
                 "||" => Some(TokenKind::Or),
                 "&&" => Some(TokenKind::And),
+                "++" => Some(TokenKind::PlusPlus),
+                "--" => Some(TokenKind::MinusMinus),
+                "**" => Some(TokenKind::AsteriskAsterisk),
                 _ => None,
             }
         }
@@ -119,17 +130,24 @@ impl Lexer {
             ')' => Some(TokenKind::RightParen),
             '{' => Some(TokenKind::LeftBrace),
             '}' => Some(TokenKind::RightBrace),
+            '[' => Some(TokenKind::LeftBracket),
+            ']' => Some(TokenKind::RightBracket),
             '<' => Some(TokenKind::LeftAngle),
             '>' => Some(TokenKind::RightAngle),
             '+' => Some(TokenKind::Plus),
             '-' => Some(TokenKind::Minus),
             '*' => Some(TokenKind::Asterisk),
             '/' => Some(TokenKind::Slash),
+            '%' => Some(TokenKind::Percent),
             ',' => Some(TokenKind::Comma),
             ';' => Some(TokenKind::Semicolon),
+            ':' => Some(TokenKind::Colon),
             '=' => Some(TokenKind::Equals),
             '!' => Some(TokenKind::Exclamation),
             '.' => Some(TokenKind::Dot),
+            '|' => Some(TokenKind::Pipe),
+            '@' => Some(TokenKind::At),
+            '?' => Some(TokenKind::Question),
             _ => None,
         };
 
@@ -238,10 +256,22 @@ impl Lexer {
                 "while" => TokenKind::Keyword(Keyword::While),
                 "true" => TokenKind::Boolean(true),
                 "false" => TokenKind::Boolean(false),
+                "null" => TokenKind::Null,
                 "class" => TokenKind::Keyword(Keyword::Class),
                 "self" => TokenKind::Keyword(Keyword::Self_),
                 "Self" => TokenKind::Keyword(Keyword::SelfType),
                 "static" => TokenKind::Keyword(Keyword::Static),
+                "let" => TokenKind::Keyword(Keyword::Let),
+                "import" => TokenKind::Keyword(Keyword::Import),
+                "const" => TokenKind::Keyword(Keyword::Const),
+                "enum" => TokenKind::Keyword(Keyword::Enum),
+                "interface" => TokenKind::Keyword(Keyword::Interface),
+                "do" => TokenKind::Keyword(Keyword::Do),
+                "try" => TokenKind::Keyword(Keyword::Try),
+                "catch" => TokenKind::Keyword(Keyword::Catch),
+                "throw" => TokenKind::Keyword(Keyword::Throw),
+                "break" => TokenKind::Keyword(Keyword::Break),
+                "continue" => TokenKind::Keyword(Keyword::Continue),
                 identifier => TokenKind::Identifier(String::from(identifier)),
             };
 
@@ -259,11 +289,30 @@ impl Lexer {
         }
 
         let start_loc: (usize, usize) = (self.line, self.column);
+        let mut segment_start: (usize, usize) = start_loc;
+        let mut interpolated: bool = false;
         self.index += 1;
         self.column += 1;
 
         let mut string_vec: Vec<char> = vec![];
         while self.index < self.source.len() && self.source[self.index] != '"' {
+            if self.source[self.index] == '$' && self.source.get(self.index + 1) == Some(&'{') {
+                let text: String = std::mem::take(&mut string_vec).into_iter().collect();
+                let kind: TokenKind = if interpolated {
+                    TokenKind::InterpolationMiddle(text)
+                } else {
+                    TokenKind::InterpolationStart(text)
+                };
+                self.index += 2;
+                self.column += 2;
+                tokens.push(Token::new(kind, segment_start, (self.line, self.column)));
+
+                self.interpolation(tokens)?;
+                segment_start = (self.line, self.column - 1);
+                interpolated = true;
+                continue;
+            }
+
             if self.source[self.index] != '\\' {
                 string_vec.push(self.source[self.index]);
                 self.index += 1;
@@ -303,15 +352,69 @@ impl Lexer {
         self.index += 1;
         self.column += 1;
 
-        tokens.push(Token::new(
-            TokenKind::String(string_vec.iter().collect()),
-            start_loc,
-            (self.line, self.column),
-        ));
+        if interpolated {
+            tokens.push(Token::new(
+                TokenKind::InterpolationEnd(string_vec.iter().collect()),
+                segment_start,
+                (self.line, self.column),
+            ));
+        } else {
+            tokens.push(Token::new(
+                TokenKind::String(string_vec.iter().collect()),
+                start_loc,
+                (self.line, self.column),
+            ));
+        }
 
         Ok(true)
     }
 
+    /// Tokenizes the expression of a `${...}` interpolation inside a string literal, starting
+    /// right after the `${` and consuming the closing `}`.
+    fn interpolation(&mut self, tokens: &mut Vec<Token>) -> Result<(), String> {
+        let start_loc: (usize, usize) = (self.line, self.column - 2);
+
+        let mut depth: usize = 1;
+        let mut inside_string: bool = false;
+        let mut end: usize = self.index;
+        while end < self.source.len() {
+            match self.source[end] {
+                '\\' if inside_string => end += 1,
+                '"' => inside_string = !inside_string,
+                '{' if !inside_string => depth += 1,
+                '}' if !inside_string => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+            end += 1;
+        }
+
+        if end >= self.source.len() {
+            return Err(format!(
+                "Unterminated interpolation starting at {}:{}",
+                start_loc.0, start_loc.1
+            ));
+        }
+
+        let token_count: usize = tokens.len();
+        self.lex_until(end, tokens)?;
+        if tokens.len() == token_count {
+            return Err(format!(
+                "Empty interpolation at {}:{}",
+                start_loc.0, start_loc.1
+            ));
+        }
+
+        self.index += 1;
+        self.column += 1;
+
+        Ok(())
+    }
+
     fn string_unicode(&mut self, string_vec: &mut Vec<char>) -> Result<(), String> {
         let start_loc: (usize, usize) = (self.line - 1, self.column - 1);
 
@@ -595,6 +698,118 @@ mod lexer_tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn string_interpolation() {
+        let result: Vec<Token> = Lexer::tokenize(r#""a ${x} b ${1} c""#).unwrap();
+        let expected: Vec<Token> = vec![
+            Token::new(
+                TokenKind::InterpolationStart(String::from("a ")),
+                (1, 1),
+                (1, 6),
+            ),
+            Token::single(TokenKind::Identifier(String::from("x")), 1, 6),
+            Token::new(
+                TokenKind::InterpolationMiddle(String::from(" b ")),
+                (1, 7),
+                (1, 13),
+            ),
+            Token::single(TokenKind::Integer(1), 1, 13),
+            Token::new(
+                TokenKind::InterpolationEnd(String::from(" c")),
+                (1, 14),
+                (1, 18),
+            ),
+            Token::single(TokenKind::EndOfFile, 1, 18),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn brackets() {
+        let result: Vec<Token> = Lexer::tokenize("a[0]").unwrap();
+        let expected: Vec<Token> = vec![
+            Token::single(TokenKind::Identifier(String::from("a")), 1, 1),
+            Token::single(TokenKind::LeftBracket, 1, 2),
+            Token::single(TokenKind::Integer(0), 1, 3),
+            Token::single(TokenKind::RightBracket, 1, 4),
+            Token::single(TokenKind::EndOfFile, 1, 5),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn map_literal() {
+        let result: Vec<Token> = Lexer::tokenize(r#"{"a": 1}"#).unwrap();
+        let expected: Vec<Token> = vec![
+            Token::single(TokenKind::LeftBrace, 1, 1),
+            Token::new(TokenKind::String(String::from("a")), (1, 2), (1, 5)),
+            Token::single(TokenKind::Colon, 1, 5),
+            Token::single(TokenKind::Integer(1), 1, 7),
+            Token::single(TokenKind::RightBrace, 1, 8),
+            Token::single(TokenKind::EndOfFile, 1, 9),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn pipes_and_or() {
+        let result: Vec<Token> = Lexer::tokenize("|x| || y").unwrap();
+        let expected: Vec<Token> = vec![
+            Token::single(TokenKind::Pipe, 1, 1),
+            Token::single(TokenKind::Identifier(String::from("x")), 1, 2),
+            Token::single(TokenKind::Pipe, 1, 3),
+            Token::new(TokenKind::Or, (1, 5), (1, 7)),
+            Token::single(TokenKind::Identifier(String::from("y")), 1, 8),
+            Token::single(TokenKind::EndOfFile, 1, 9),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn attribute() {
+        let result: Vec<Token> = Lexer::tokenize("@inline").unwrap();
+        let expected: Vec<Token> = vec![
+            Token::single(TokenKind::At, 1, 1),
+            Token::new(
+                TokenKind::Identifier(String::from("inline")),
+                (1, 2),
+                (1, 8),
+            ),
+            Token::single(TokenKind::EndOfFile, 1, 8),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn null_and_optional_type() {
+        let result: Vec<Token> = Lexer::tokenize("int? x = null;").unwrap();
+        let expected: Vec<Token> = vec![
+            Token::new(TokenKind::Identifier(String::from("int")), (1, 1), (1, 4)),
+            Token::single(TokenKind::Question, 1, 4),
+            Token::single(TokenKind::Identifier(String::from("x")), 1, 6),
+            Token::single(TokenKind::Equals, 1, 8),
+            Token::new(TokenKind::Null, (1, 10), (1, 14)),
+            Token::single(TokenKind::Semicolon, 1, 14),
+            Token::single(TokenKind::EndOfFile, 1, 15),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn increment_decrement() {
+        let result: Vec<Token> = Lexer::tokenize("i++; j--;").unwrap();
+        let expected: Vec<Token> = vec![
+            Token::single(TokenKind::Identifier(String::from("i")), 1, 1),
+            Token::new(TokenKind::PlusPlus, (1, 2), (1, 4)),
+            Token::single(TokenKind::Semicolon, 1, 4),
+            Token::single(TokenKind::Identifier(String::from("j")), 1, 6),
+            Token::new(TokenKind::MinusMinus, (1, 7), (1, 9)),
+            Token::single(TokenKind::Semicolon, 1, 9),
+            Token::single(TokenKind::EndOfFile, 1, 10),
+        ];
+        assert_eq!(result, expected);
+    }
+
     #[test]
     fn boolean_literals() {
         let result: Vec<Token> = Lexer::tokenize("true false").unwrap();
@@ -605,4 +820,75 @@ mod lexer_tests {
         ];
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn loop_control_keywords() {
+        let result: Vec<Token> = Lexer::tokenize("break; continue;").unwrap();
+        let expected: Vec<Token> = vec![
+            Token::new(TokenKind::Keyword(Keyword::Break), (1, 1), (1, 6)),
+            Token::single(TokenKind::Semicolon, 1, 6),
+            Token::new(TokenKind::Keyword(Keyword::Continue), (1, 8), (1, 16)),
+            Token::single(TokenKind::Semicolon, 1, 16),
+            Token::single(TokenKind::EndOfFile, 1, 17),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn modulo_and_power_operators() {
+        let result: Vec<Token> = Lexer::tokenize("a % b ** c").unwrap();
+        let expected: Vec<Token> = vec![
+            Token::new(TokenKind::Identifier(String::from("a")), (1, 1), (1, 2)),
+            Token::single(TokenKind::Percent, 1, 3),
+            Token::new(TokenKind::Identifier(String::from("b")), (1, 5), (1, 6)),
+            Token::new(TokenKind::AsteriskAsterisk, (1, 7), (1, 9)),
+            Token::new(TokenKind::Identifier(String::from("c")), (1, 10), (1, 11)),
+            Token::single(TokenKind::EndOfFile, 1, 11),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn error_handling_keywords() {
+        let result: Vec<Token> = Lexer::tokenize("try catch throw").unwrap();
+        let expected: Vec<Token> = vec![
+            Token::new(TokenKind::Keyword(Keyword::Try), (1, 1), (1, 4)),
+            Token::new(TokenKind::Keyword(Keyword::Catch), (1, 5), (1, 10)),
+            Token::new(TokenKind::Keyword(Keyword::Throw), (1, 11), (1, 16)),
+            Token::single(TokenKind::EndOfFile, 1, 16),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn tokens_display_as_source() {
+        let source: &str = r#"if (x.y >= 1.5) { return "a\"b"; } Self ** null"#;
+        let spelled: Vec<String> = Lexer::tokenize(source)
+            .unwrap()
+            .iter()
+            .map(|token| token.kind.to_string())
+            .collect();
+        assert_eq!(
+            spelled,
+            vec![
+                "if",
+                "(",
+                "x",
+                ".",
+                "y",
+                ">=",
+                "1.5",
+                ")",
+                "{",
+                "return",
+                r#""a\"b""#,
+                ";",
+                "}",
+                "Self",
+                "**",
+                "null",
+                "end of input"
+            ]
+        );
+    }
 }
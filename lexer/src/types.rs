@@ -1,5 +1,7 @@
 //! Contains the types used in the lexer implementation.
 
+use std::fmt::{self, Display, Formatter};
+
 /// Defines the different keywords recognized by the lexer.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Keyword {
@@ -11,6 +13,8 @@ pub enum Keyword {
     Else,
     /// While keyword, used for looping constructs.
     While,
+    /// Do keyword, used for loops that run their body at least once.
+    Do,
     /// Class keyword, used for defining classes.
     Class,
     /// Self keyword, used to refer to the current instance in class methods.
@@ -19,6 +23,52 @@ pub enum Keyword {
     SelfType,
     /// Static keyword, used for marking class members as public.
     Static,
+    /// Let keyword, used for declaring variables without a leading type.
+    Let,
+    /// Import keyword, used for importing other source files.
+    Import,
+    /// Const keyword, used for declaring compile-time constants.
+    Const,
+    /// Enum keyword, used for declaring enums.
+    Enum,
+    /// Interface keyword, used for declaring interfaces.
+    Interface,
+    /// Try keyword, used for guarding a block against errors.
+    Try,
+    /// Catch keyword, used for handling errors raised inside a try block.
+    Catch,
+    /// Throw keyword, used for raising an error.
+    Throw,
+    /// Break keyword, used for leaving the innermost loop.
+    Break,
+    /// Continue keyword, used for skipping to the next iteration of the innermost loop.
+    Continue,
+}
+
+impl Display for Keyword {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Return => "return",
+            Self::If => "if",
+            Self::Else => "else",
+            Self::While => "while",
+            Self::Do => "do",
+            Self::Class => "class",
+            Self::Self_ => "self",
+            Self::SelfType => "Self",
+            Self::Static => "static",
+            Self::Let => "let",
+            Self::Import => "import",
+            Self::Const => "const",
+            Self::Enum => "enum",
+            Self::Interface => "interface",
+            Self::Try => "try",
+            Self::Catch => "catch",
+            Self::Throw => "throw",
+            Self::Break => "break",
+            Self::Continue => "continue",
+        })
+    }
 }
 
 /// Defines the different kinds of tokens that can be recognized by the lexer. Each variant may
@@ -33,6 +83,14 @@ pub enum TokenKind {
     String(String),
     /// Represents a boolean literal. [true | false]
     Boolean(bool),
+    /// Represents the null literal.
+    Null,
+    /// Represents the text of an interpolated string before its first `${...}` interpolation.
+    InterpolationStart(String),
+    /// Represents the text of an interpolated string between two `${...}` interpolations.
+    InterpolationMiddle(String),
+    /// Represents the text of an interpolated string after its last `${...}` interpolation.
+    InterpolationEnd(String),
     /// +
     Plus,
     /// -
@@ -41,6 +99,8 @@ pub enum TokenKind {
     Asterisk,
     /// /
     Slash,
+    /// %
+    Percent,
     /// (
     LeftParen,
     /// )
@@ -49,6 +109,10 @@ pub enum TokenKind {
     LeftBrace,
     /// }
     RightBrace,
+    /// [
+    LeftBracket,
+    /// ]
+    RightBracket,
     /// <
     LeftAngle,
     /// >
@@ -59,6 +123,8 @@ pub enum TokenKind {
     Equals,
     /// ;
     Semicolon,
+    /// :
+    Colon,
     /// >=
     GreaterThanOrEqual,
     /// <=
@@ -71,10 +137,22 @@ pub enum TokenKind {
     Or,
     /// &&
     And,
+    /// ++
+    PlusPlus,
+    /// --
+    MinusMinus,
+    /// **
+    AsteriskAsterisk,
     /// !
     Exclamation,
     /// .
     Dot,
+    /// |
+    Pipe,
+    /// @
+    At,
+    /// ?
+    Question,
     /// Represents an identifier.
     Identifier(String),
     /// Represents a keyword.
@@ -83,6 +161,57 @@ pub enum TokenKind {
     EndOfFile,
 }
 
+/// Writes the token the way it is spelled in the source code, or a short description for the end
+/// of the input.
+impl Display for TokenKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::Float(value) => write!(f, "{value:?}"),
+            Self::String(value) => write!(f, "{value:?}"),
+            Self::Boolean(value) => write!(f, "{value}"),
+            Self::InterpolationStart(text) => write!(f, "\"{text}${{"),
+            Self::InterpolationMiddle(text) => write!(f, "}}{text}${{"),
+            Self::InterpolationEnd(text) => write!(f, "}}{text}\""),
+            Self::Identifier(name) => f.write_str(name),
+            Self::Keyword(keyword) => write!(f, "{keyword}"),
+            Self::Null => f.write_str("null"),
+            Self::Plus => f.write_str("+"),
+            Self::Minus => f.write_str("-"),
+            Self::Asterisk => f.write_str("*"),
+            Self::Slash => f.write_str("/"),
+            Self::Percent => f.write_str("%"),
+            Self::LeftParen => f.write_str("("),
+            Self::RightParen => f.write_str(")"),
+            Self::LeftBrace => f.write_str("{"),
+            Self::RightBrace => f.write_str("}"),
+            Self::LeftBracket => f.write_str("["),
+            Self::RightBracket => f.write_str("]"),
+            Self::LeftAngle => f.write_str("<"),
+            Self::RightAngle => f.write_str(">"),
+            Self::Comma => f.write_str(","),
+            Self::Equals => f.write_str("="),
+            Self::Semicolon => f.write_str(";"),
+            Self::Colon => f.write_str(":"),
+            Self::GreaterThanOrEqual => f.write_str(">="),
+            Self::LessThanOrEqual => f.write_str("<="),
+            Self::EqualsEquals => f.write_str("=="),
+            Self::NotEquals => f.write_str("!="),
+            Self::Or => f.write_str("||"),
+            Self::And => f.write_str("&&"),
+            Self::PlusPlus => f.write_str("++"),
+            Self::MinusMinus => f.write_str("--"),
+            Self::AsteriskAsterisk => f.write_str("**"),
+            Self::Exclamation => f.write_str("!"),
+            Self::Dot => f.write_str("."),
+            Self::Pipe => f.write_str("|"),
+            Self::At => f.write_str("@"),
+            Self::Question => f.write_str("?"),
+            Self::EndOfFile => f.write_str("end of input"),
+        }
+    }
+}
+
 /// Represents a token with its kind and position in the source code. The kind contains the value.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Token {
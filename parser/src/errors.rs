@@ -0,0 +1,228 @@
+//! Contains the different errors that can come up during parsing
+
+use lexer::types::{Token, TokenKind};
+
+use crate::types::Span;
+
+/// Represents an error that can occur during parsing, including the type of error and the span
+/// of the offending token in the source code.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// The type of parse error that occurred.
+    pub error_type: ParseErrorType,
+    /// The span of the token that caused the error.
+    pub span: Span,
+}
+
+impl ParseError {
+    /// Creates a new error of the given type, spanning the given token.
+    #[must_use]
+    pub const fn new(error_type: ParseErrorType, token: &Token) -> Self {
+        Self {
+            error_type,
+            span: Span {
+                start: token.start,
+                end: token.end,
+            },
+        }
+    }
+
+    /// Creates an [`ParseErrorType::UnexpectedToken`] error for the given token, or an
+    /// [`ParseErrorType::UnexpectedEof`] error if the token marks the end of the input.
+    #[must_use]
+    pub fn unexpected(token: &Token, expected: &[&str]) -> Self {
+        let expected: Vec<String> = expected.iter().map(ToString::to_string).collect();
+        let error_type: ParseErrorType = if token.kind == TokenKind::EndOfFile {
+            ParseErrorType::UnexpectedEof { expected }
+        } else {
+            ParseErrorType::UnexpectedToken {
+                expected,
+                found: token.kind.clone(),
+            }
+        };
+
+        Self::new(error_type, token)
+    }
+
+    /// Returns the full error message.
+    #[must_use]
+    pub fn error_message(&self) -> String {
+        let mut message: String = String::new();
+
+        message.push_str("ParseError: ");
+        message.push_str(self.error_type.error_name());
+        message.push_str(" at [");
+        message.push_str(&self.span.start.0.to_string());
+        message.push(':');
+        message.push_str(&self.span.start.1.to_string());
+        message.push_str("]: ");
+        message.push_str(&self.error_type.message());
+
+        message
+    }
+
+    /// Prints the error message to stderr.
+    pub fn print(&self) {
+        eprintln!("{}", self.error_message());
+    }
+}
+
+/// Represents an error that can occur during parsing, such as unexpected tokens or constructs
+/// used in places where they are not allowed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseErrorType {
+    /// The parser found a token that cannot appear at this position.
+    UnexpectedToken {
+        /// Descriptions of what would have been accepted instead, e.g. `;` or `type`.
+        expected: Vec<String>,
+        /// The token that was found.
+        found: TokenKind,
+    },
+    /// The input ended while the parser still expected more tokens.
+    UnexpectedEof {
+        /// Descriptions of what would have been accepted instead.
+        expected: Vec<String>,
+    },
+    /// User tried to assign to an expression that cannot be assigned to, e.g. a function call.
+    InvalidAssignmentTarget(String),
+    /// User used a statement or expression in the global scope where it is not allowed.
+    IllegalInGlobalScope(String),
+    /// User tried to declare a class outside the global scope.
+    IllegalClassDeclaration,
+    /// User tried to declare an enum outside the global scope.
+    IllegalEnumDeclaration,
+    /// User tried to declare an interface outside the global scope.
+    IllegalInterfaceDeclaration,
+    /// User tried to declare a variable outside a function or method body.
+    IllegalVariableDeclaration,
+    /// User tried to import a module outside the global scope.
+    IllegalImport,
+    /// User attached attributes to something other than a function, method or class declaration.
+    MisplacedAttribute(String),
+    /// User used `self` or `Self` outside a class.
+    SelfOutsideClass(String),
+    /// User used the `static` keyword outside a class or on something other than a method or
+    /// field declaration.
+    IllegalStatic,
+    /// User wrote an `else` without a preceding `if`.
+    ElseWithoutIf,
+    /// User wrote a `catch` without a preceding `try`.
+    CatchWithoutTry,
+    /// User chained comparison operators, e.g. `a < b < c`.
+    ChainedComparison,
+    /// User passed a positional argument after a named one, e.g. `f(width: 3, 4)`.
+    PositionalAfterNamedArgument,
+}
+
+impl ParseErrorType {
+    /// Returns the error message for this parse error type.
+    #[must_use]
+    pub fn message(&self) -> String {
+        match self {
+            Self::UnexpectedToken { expected, found } => {
+                if expected.is_empty() {
+                    format!("Unexpected token '{found}'")
+                } else {
+                    format!("Expected {}, found '{found}'", expected_list(expected))
+                }
+            }
+            Self::UnexpectedEof { expected } => {
+                if expected.is_empty() {
+                    String::from("Unexpected end of input")
+                } else {
+                    format!("Expected {}, found end of input", expected_list(expected))
+                }
+            }
+            Self::InvalidAssignmentTarget(kind) => {
+                format!("Cannot assign to an expression of kind '{kind}'")
+            }
+            Self::IllegalInGlobalScope(what) => {
+                format!("{what} is not allowed in the global scope")
+            }
+            Self::IllegalClassDeclaration => {
+                String::from("Class declarations are only allowed in the global scope")
+            }
+            Self::IllegalEnumDeclaration => {
+                String::from("Enum declarations are only allowed in the global scope")
+            }
+            Self::IllegalInterfaceDeclaration => {
+                String::from("Interface declarations are only allowed in the global scope")
+            }
+            Self::IllegalVariableDeclaration => {
+                String::from("Variable declarations are only allowed inside functions and methods")
+            }
+            Self::IllegalImport => String::from("Imports are only allowed in the global scope"),
+            Self::MisplacedAttribute(kind) => format!(
+                "Attributes can only be attached to function, method and class declarations, \
+                 found '{kind}'"
+            ),
+            Self::SelfOutsideClass(keyword) => format!("Illegal use of '{keyword}' outside class"),
+            Self::IllegalStatic => String::from(
+                "The 'static' keyword can only be used on method and field declarations inside a \
+                 class",
+            ),
+            Self::ElseWithoutIf => String::from("Unexpected 'else' without matching 'if'"),
+            Self::CatchWithoutTry => String::from("Unexpected 'catch' without matching 'try'"),
+            Self::ChainedComparison => String::from("Chained comparison operators are not allowed"),
+            Self::PositionalAfterNamedArgument => {
+                String::from("Positional arguments cannot follow named arguments")
+            }
+        }
+    }
+
+    /// Returns the name of the parse error type.
+    #[must_use]
+    pub const fn error_name(&self) -> &str {
+        match self {
+            Self::UnexpectedToken { .. } => "UnexpectedToken",
+            Self::UnexpectedEof { .. } => "UnexpectedEof",
+            Self::InvalidAssignmentTarget(_) => "InvalidAssignmentTarget",
+            Self::IllegalInGlobalScope(_) => "IllegalInGlobalScope",
+            Self::IllegalClassDeclaration => "IllegalClassDeclaration",
+            Self::IllegalEnumDeclaration => "IllegalEnumDeclaration",
+            Self::IllegalInterfaceDeclaration => "IllegalInterfaceDeclaration",
+            Self::IllegalVariableDeclaration => "IllegalVariableDeclaration",
+            Self::IllegalImport => "IllegalImport",
+            Self::MisplacedAttribute(_) => "MisplacedAttribute",
+            Self::SelfOutsideClass(_) => "SelfOutsideClass",
+            Self::IllegalStatic => "IllegalStatic",
+            Self::ElseWithoutIf => "ElseWithoutIf",
+            Self::CatchWithoutTry => "CatchWithoutTry",
+            Self::ChainedComparison => "ChainedComparison",
+            Self::PositionalAfterNamedArgument => "PositionalAfterNamedArgument",
+        }
+    }
+}
+
+/// Joins the expected descriptions into a readable list, e.g. `'a', 'b' or 'c'`.
+fn expected_list(expected: &[String]) -> String {
+    let quoted: Vec<String> = expected.iter().map(|e| format!("'{e}'")).collect();
+    match quoted.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} or {last}", rest.join(", ")),
+        Some((last, _)) => last.clone(),
+        None => String::new(),
+    }
+}
+
+/// Appends the given line of `source` to `message`, with a caret below the given column. Lines
+/// and columns start at 1. Returns `message` unchanged if the line doesn't exist.
+#[must_use]
+pub fn with_source(message: String, source: &str, line: usize, column: usize) -> String {
+    let Some(source_line) = line
+        .checked_sub(1)
+        .and_then(|index| source.lines().nth(index))
+    else {
+        return message;
+    };
+
+    let gutter: String = line.to_string();
+    let padding: String = " ".repeat(gutter.len());
+    // Keep tabs so the caret lines up with the source line however wide tabs are displayed
+    let indent: String = source_line
+        .chars()
+        .take(column.saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+
+    format!("{message}\n{padding} |\n{gutter} | {source_line}\n{padding} | {indent}^")
+}
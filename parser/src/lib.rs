@@ -1,13 +1,15 @@
 //! Contains the parser implementation for the programming language.
+pub mod errors;
 pub mod types;
 
 use std::mem::discriminant;
 
 use lexer::types::{Keyword, Token, TokenKind};
 
+use crate::errors::{ParseError, ParseErrorType};
 use crate::types::{
-    BinaryOperator, Expr, Expression, Literal, Program, Span, Spanned, Statement, Stmt,
-    UnaryOperator,
+    Associativity, Attribute, BinaryOperator, EnumVariant, Expr, Expression, Literal,
+    MethodSignature, Parameter, Program, Span, Spanned, Statement, Stmt, UnaryOperator,
 };
 
 /// The parser struct responsible for parsing tokens into an AST.
@@ -21,20 +23,11 @@ pub struct Parser {
 }
 
 impl Parser {
-    const COMPARISON_TOKEN: [TokenKind; 6] = [
-        TokenKind::EqualsEquals,
-        TokenKind::NotEquals,
-        TokenKind::LeftAngle,
-        TokenKind::RightAngle,
-        TokenKind::LessThanOrEqual,
-        TokenKind::GreaterThanOrEqual,
-    ];
-
     /// Parses the tokens and returns the root of the AST.
     ///
     /// # Errors
     /// Unexpected end of input or invalid syntax.
-    pub fn parse(tokens: Vec<Token>) -> Result<Program, String> {
+    pub fn parse(tokens: Vec<Token>) -> Result<Program, ParseError> {
         let mut parser: Self = Self {
             tokens,
             index: 0,
@@ -53,14 +46,32 @@ impl Parser {
         Ok(Program { statements })
     }
 
-    fn is_eof(&self) -> Result<bool, String> {
+    fn is_eof(&self) -> Result<bool, ParseError> {
         Ok(matches!(self.peek()?.kind, TokenKind::EndOfFile))
     }
 
-    fn peek(&self) -> Result<&Token, String> {
+    fn peek(&self) -> Result<&Token, ParseError> {
         self.tokens
             .get(self.index)
-            .ok_or_else(|| "Unexpected end of input".to_string())
+            .ok_or_else(|| self.unexpected(&[]))
+    }
+
+    /// Returns the token at `index`, falling back to the last token (end of input).
+    fn token_at(&self, index: usize) -> &Token {
+        self.tokens
+            .get(index)
+            .or_else(|| self.tokens.last())
+            .expect("Token list always ends with an end of file token")
+    }
+
+    /// Creates an error of the given type spanning the current token.
+    fn error(&self, error_type: ParseErrorType) -> ParseError {
+        ParseError::new(error_type, self.token_at(self.index))
+    }
+
+    /// Creates an unexpected token error for the current token.
+    fn unexpected(&self, expected: &[&str]) -> ParseError {
+        ParseError::unexpected(self.token_at(self.index), expected)
     }
 
     const fn advance(&mut self) {
@@ -76,31 +87,24 @@ impl Parser {
         false
     }
 
-    fn expect_token(&mut self, kind: &lexer::types::TokenKind) -> Result<&Token, String> {
+    fn expect_token(&mut self, kind: &lexer::types::TokenKind) -> Result<&Token, ParseError> {
         if self.match_token(kind) {
             self.advance();
             Ok(&self.tokens[self.index - 1])
-        } else if let Ok(token) = self.peek() {
-            Err(format!(
-                "Expected token '{:?}', found '{:?}' at {}:{}",
-                kind, token.kind, token.start.0, token.start.1
-            ))
         } else {
-            Err(format!("Expected token '{kind:?}', found end of input"))
+            Err(self.unexpected(&[&kind.to_string()]))
         }
     }
 
-    fn expect_token_kind(&mut self, kind: &lexer::types::TokenKind) -> Result<&Token, String> {
-        if discriminant(&self.peek()?.kind) == discriminant(kind) {
-            self.advance();
-            Ok(&self.tokens[self.index - 1])
-        } else if let Ok(token) = self.peek() {
-            Err(format!(
-                "Expected token '{:?}', found '{:?}' at {}:{}",
-                kind, token.kind, token.start.0, token.start.1
-            ))
-        } else {
-            Err(format!("Expected token '{kind:?}', found end of input"))
+    /// Consumes an identifier token and returns its name.
+    fn expect_identifier(&mut self) -> Result<String, ParseError> {
+        match &self.peek()?.kind {
+            TokenKind::Identifier(name) => {
+                let name: String = name.clone();
+                self.advance();
+                Ok(name)
+            }
+            _ => Err(self.unexpected(&["identifier"])),
         }
     }
 
@@ -117,36 +121,104 @@ impl Parser {
         true
     }
 
+    /// Returns the amount of tokens forming a type starting at token index `at`, if any.
+    fn type_length(&self, at: usize) -> Option<usize> {
+        if !matches!(
+            self.tokens.get(at)?.kind,
+            TokenKind::Identifier(_) | TokenKind::Keyword(Keyword::SelfType)
+        ) {
+            return None;
+        }
+
+        let mut length: usize = 1;
+        while matches!(
+            self.tokens.get(at + length).map(|t| &t.kind),
+            Some(TokenKind::LeftBracket)
+        ) && matches!(
+            self.tokens.get(at + length + 1).map(|t| &t.kind),
+            Some(TokenKind::RightBracket)
+        ) {
+            length += 2;
+        }
+
+        if matches!(
+            self.tokens.get(at + length).map(|t| &t.kind),
+            Some(TokenKind::Question)
+        ) {
+            length += 1;
+        }
+
+        Some(length)
+    }
+
+    fn parse_type(&mut self) -> Result<String, ParseError> {
+        let token: &Token = self.peek()?;
+        let mut type_: String = match &token.kind {
+            TokenKind::Identifier(name) => name.clone(),
+            TokenKind::Keyword(Keyword::SelfType) => match &self.inside_class {
+                Some(class_name) => class_name.clone(),
+                None => {
+                    return Err(ParseError::new(
+                        ParseErrorType::SelfOutsideClass(String::from("Self")),
+                        token,
+                    ));
+                }
+            },
+            _ => return Err(ParseError::unexpected(token, &["type"])),
+        };
+        self.advance();
+
+        while self.check_next_tokens(&[TokenKind::LeftBracket, TokenKind::RightBracket]) {
+            self.advance();
+            self.advance();
+            type_.push_str("[]");
+        }
+
+        if self.match_token(&TokenKind::Question) {
+            self.advance();
+            type_.push('?');
+        }
+
+        Ok(type_)
+    }
+
     fn parse_postfix_chain(
         &mut self,
         mut expr: Expr,
         start: (usize, usize),
-    ) -> Result<Expr, String> {
+    ) -> Result<Expr, ParseError> {
         loop {
-            match self.peek()?.kind.clone() {
+            match self.peek()?.kind {
                 TokenKind::Dot => {
                     self.advance();
-                    let member: &Token =
-                        self.expect_token_kind(&TokenKind::Identifier(String::new()))?;
+                    let end: (usize, usize) = self.peek()?.end;
+                    let member: String = self.expect_identifier()?;
 
                     expr = Spanned {
                         node: Expression::MemberAccess {
                             object: Box::new(expr),
-                            member: match &member.kind {
-                                TokenKind::Identifier(name) => name.clone(),
-                                _ => unreachable!(),
-                            },
-                        },
-                        span: Span {
-                            start,
-                            end: member.end,
+                            member,
                         },
+                        span: Span { start, end },
                     };
                 }
                 TokenKind::LeftParen => {
                     self.advance();
                     expr = self.parse_function_call(Box::new(expr), start)?;
                 }
+                TokenKind::LeftBracket => {
+                    self.advance();
+                    let index: Expr = self.parse_expression()?;
+                    let end: (usize, usize) = self.expect_token(&TokenKind::RightBracket)?.end;
+
+                    expr = Spanned {
+                        node: Expression::Index {
+                            object: Box::new(expr),
+                            index: Box::new(index),
+                        },
+                        span: Span { start, end },
+                    };
+                }
                 _ => break,
             }
         }
@@ -154,169 +226,144 @@ impl Parser {
         Ok(expr)
     }
 
-    #[allow(clippy::too_many_lines)]
-    #[allow(clippy::cognitive_complexity)]
-    fn parse_statement(&mut self) -> Result<Stmt, String> {
+    fn parse_statement(&mut self) -> Result<Stmt, ParseError> {
         if matches!(self.peek()?.kind, TokenKind::Keyword(_)) {
             return self.parse_keyworded();
         }
 
-        let first_token: Token = self.peek()?.clone();
-        let first_ident: String = if let TokenKind::Identifier(name) = &first_token.kind {
-            name.clone()
-        } else {
-            let expr: Spanned<Expression> = self.parse_expression()?;
-            let start: (usize, usize) = expr.span.start;
-            let end: (usize, usize) = self.expect_token(&TokenKind::Semicolon)?.end;
-            return Ok(Spanned {
-                node: Statement::Expression(expr),
-                span: Span { start, end },
-            });
-        };
+        if self.match_token(&TokenKind::At) {
+            return self.parse_attributed_declaration();
+        }
+
+        if !matches!(self.peek()?.kind, TokenKind::Identifier(_)) {
+            return self.parse_expression_statement();
+        }
+
+        if let Some(type_length) = self.type_length(self.index)
+            && let Some(TokenKind::Identifier(_)) =
+                self.tokens.get(self.index + type_length).map(|t| &t.kind)
+        {
+            let next: &Token = self.token_at(self.index + type_length + 1);
+
+            return match next.kind {
+                TokenKind::Equals | TokenKind::Semicolon => self.parse_variable_declaration(),
+                TokenKind::LeftParen | TokenKind::LeftAngle => self.parse_function_declaration(),
+                _ => Err(ParseError::unexpected(next, &["=", ";", "(", "<"])),
+            };
+        }
+
+        self.parse_assignment_or_expression()
+    }
 
+    fn parse_attributed_declaration(&mut self) -> Result<Stmt, ParseError> {
         let start: (usize, usize) = self.peek()?.start;
-        self.advance();
 
-        let second_token_kind: TokenKind = self.peek()?.kind.clone();
-        self.advance();
+        let mut attributes: Vec<Attribute> = Vec::new();
+        while self.match_token(&TokenKind::At) {
+            attributes.push(self.parse_attribute()?);
+        }
 
-        match second_token_kind {
-            TokenKind::Identifier(_) => match self.peek()?.kind.clone() {
-                TokenKind::Equals | TokenKind::Semicolon => {
-                    self.index -= 2;
-                    self.parse_variable_declaration()
-                }
-                TokenKind::LeftParen => {
-                    self.index -= 2;
-                    self.parse_function_declaration()
-                }
-                _ => {
-                    let peek: &Token = self.peek()?;
-                    Err(format!(
-                        "Invalid token following two identifiers: '{:?}' at {}:{}",
-                        peek.kind, peek.start.0, peek.start.1
-                    ))
-                }
-            },
-            TokenKind::Dot => {
-                self.index -= 1; // Set current token to dot. parse_postfix_chain MUST see the dot.
-                self.parse_statement_with_member(
-                    &Spanned {
-                        node: Expression::Identifier(first_ident),
-                        span: Span {
-                            start,
-                            end: first_token.end,
-                        },
-                    },
-                    start,
-                )
+        let mut stmt: Stmt = self.parse_statement()?;
+        match &mut stmt.node {
+            Statement::FunctionDeclaration {
+                attributes: target, ..
             }
-            TokenKind::Equals => {
-                self.index -= 2;
-                self.parse_assignment()
-            }
-            TokenKind::LeftParen => {
-                let end: (usize, usize) = self.tokens[self.index - 1].end;
-                let expr: Expr = self.parse_function_call(
-                    Box::new(Spanned {
-                        node: Expression::Identifier(first_ident),
-                        span: Span { start, end },
-                    }),
-                    start,
-                )?;
-                if self.peek()?.kind == TokenKind::Dot {
-                    return self.parse_statement_with_member(&expr, start);
-                }
-                let end: (usize, usize) = self.expect_token(&TokenKind::Semicolon)?.end;
-                Ok(Spanned {
-                    node: Statement::Expression(expr),
-                    span: Span { start, end },
-                })
+            | Statement::MethodDeclaration {
+                attributes: target, ..
             }
-            TokenKind::Semicolon => {
-                self.index -= 1;
-                let expr: Expr = self.parse_expression()?;
-                let end: (usize, usize) = self.expect_token(&TokenKind::Semicolon)?.end;
-                Ok(Spanned {
-                    node: Statement::Expression(expr),
-                    span: Span { start, end },
-                })
+            | Statement::ClassDeclaration {
+                attributes: target, ..
+            } => {
+                attributes.append(target);
+                *target = attributes;
             }
-            _ => {
-                let err_start: (usize, usize) = self.peek()?.start;
-                Err(format!(
-                    "Unexpected token after identifier: {:?} at {}:{}",
-                    first_ident, err_start.0, err_start.1
-                ))
+            node => {
+                return Err(ParseError {
+                    error_type: ParseErrorType::MisplacedAttribute(String::from(node.name())),
+                    span: stmt.span,
+                });
             }
         }
+
+        stmt.span.start = start;
+        Ok(stmt)
     }
 
-    fn parse_statement_with_member(
-        &mut self,
-        expr: &Expr,
-        start: (usize, usize),
-    ) -> Result<Stmt, String> {
-        if !self.outside_global_scope {
-            return Err(format!(
-                "Member access is not allowed in the global scope at {}:{}",
-                self.peek()?.start.0,
-                self.peek()?.start.1
-            ));
-        }
+    fn parse_attribute(&mut self) -> Result<Attribute, ParseError> {
+        self.expect_token(&TokenKind::At)?;
 
-        let expr: Expr = self.parse_postfix_chain(expr.clone(), start)?;
+        let name: String = self.expect_identifier()?;
 
-        match self.peek()?.kind.clone() {
-            TokenKind::Equals => self.parse_named_assignment(Box::new(expr), start),
-            TokenKind::Semicolon => {
-                let end: (usize, usize) = self.expect_token(&TokenKind::Semicolon)?.end;
-                Ok(Spanned {
-                    node: Statement::Expression(expr),
-                    span: Span { start, end },
-                })
+        let mut arguments: Vec<Literal> = Vec::new();
+        if self.match_token(&TokenKind::LeftParen) {
+            self.advance();
+            while !self.match_token(&TokenKind::RightParen) {
+                let Expression::Literal(literal) = self.parse_literal()?.node else {
+                    unreachable!()
+                };
+                arguments.push(literal);
+
+                if !self.match_token(&TokenKind::RightParen) {
+                    self.expect_token(&TokenKind::Comma)?;
+                }
             }
-            TokenKind::Dot | TokenKind::LeftParen => {
-                let expr: Expr = self.parse_postfix_chain(expr, start)?;
-                let end: (usize, usize) = self.expect_token(&TokenKind::Semicolon)?.end;
-                Ok(Spanned {
-                    node: Statement::Expression(expr),
-                    span: Span { start, end },
-                })
+            self.expect_token(&TokenKind::RightParen)?;
+        }
+
+        Ok(Attribute { name, arguments })
+    }
+
+    /// Parses a statement starting with an identifier or `self`, which is either an assignment,
+    /// an increment/decrement or an expression statement.
+    fn parse_assignment_or_expression(&mut self) -> Result<Stmt, ParseError> {
+        let checkpoint: usize = self.index;
+        let start: (usize, usize) = self.peek()?.start;
+        let target: Expr = self.parse_primary()?;
+
+        match self.peek()?.kind {
+            TokenKind::Equals => self.parse_named_assignment(Box::new(target), start),
+            TokenKind::PlusPlus | TokenKind::MinusMinus => {
+                self.parse_increment(Box::new(target), start)
             }
             _ => {
-                let err_start: (usize, usize) = self.peek()?.start;
-                Err(format!(
-                    "Unexpected token after member access at {}:{}",
-                    err_start.0, err_start.1
-                ))
+                self.index = checkpoint;
+                self.parse_expression_statement()
             }
         }
     }
 
+    fn parse_expression_statement(&mut self) -> Result<Stmt, ParseError> {
+        let expr: Expr = self.parse_expression()?;
+        let start: (usize, usize) = expr.span.start;
+        let end: (usize, usize) = self.expect_token(&TokenKind::Semicolon)?.end;
+        Ok(Spanned {
+            node: Statement::Expression(expr),
+            span: Span { start, end },
+        })
+    }
+
     #[allow(clippy::too_many_lines)]
-    fn parse_keyworded(&mut self) -> Result<Stmt, String> {
-        let kind: TokenKind = self.peek()?.kind.clone();
-        match kind {
-            TokenKind::Keyword(keyword) => match keyword {
+    fn parse_keyworded(&mut self) -> Result<Stmt, ParseError> {
+        match self.peek()?.kind {
+            TokenKind::Keyword(ref keyword) => match keyword {
                 Keyword::If => self.parse_if_statement(),
-                Keyword::Else => Err(format!(
-                    "Unexpected 'else' without matching 'if' at {}:{}",
-                    self.peek()?.start.0,
-                    self.peek()?.start.1
-                )),
+                Keyword::Else => Err(self.error(ParseErrorType::ElseWithoutIf)),
                 Keyword::While => self.parse_while_loop(),
+                Keyword::Do => self.parse_do_while_loop(),
+                Keyword::Try => self.parse_try_statement(),
+                Keyword::Catch => Err(self.error(ParseErrorType::CatchWithoutTry)),
+                Keyword::Throw => self.parse_throw_statement(),
+                Keyword::Break | Keyword::Continue => self.parse_loop_control(),
                 Keyword::Return => {
                     if !self.outside_global_scope {
-                        return Err(format!(
-                            "The 'return' keyword cannot be used in the global scope at {}:{}",
-                            self.peek()?.start.0,
-                            self.peek()?.start.1
-                        ));
+                        return Err(self.error(ParseErrorType::IllegalInGlobalScope(
+                            String::from("'return'"),
+                        )));
                     }
 
-                    let start: (usize, usize) =
-                        self.expect_token(&TokenKind::Keyword(Keyword::Return))?.end;
+                    let start: (usize, usize) = self
+                        .expect_token(&TokenKind::Keyword(Keyword::Return))?
+                        .start;
                     let expr: Option<Expr> = if self.match_token(&TokenKind::Semicolon) {
                         None
                     } else {
@@ -329,87 +376,51 @@ impl Parser {
                     })
                 }
                 Keyword::Class => self.parse_class_declaration(),
+                Keyword::Let => self.parse_let_declaration(),
+                Keyword::Import => self.parse_import(),
+                Keyword::Const => self.parse_const_declaration(),
+                Keyword::Enum => self.parse_enum_declaration(),
+                Keyword::Interface => self.parse_interface_declaration(),
                 Keyword::Self_ => {
-                    let token: Token = self
-                        .expect_token(&TokenKind::Keyword(Keyword::Self_))?
-                        .clone();
-                    let start: (usize, usize) = token.start;
-                    let end: (usize, usize) = token.end;
-
                     if self.inside_class.is_none() {
-                        return Err(format!(
-                            "Illegal use of 'self' outside class at {}:{}",
-                            token.start.0, token.start.1
-                        ));
+                        return Err(
+                            self.error(ParseErrorType::SelfOutsideClass(String::from("self")))
+                        );
                     }
 
-                    let mut expr: Expr = Spanned {
-                        node: Expression::Self_,
-                        span: Span { start, end },
-                    };
-
-                    expr = self.parse_postfix_chain(expr, start)?;
-
-                    match self.peek()?.kind {
-                        TokenKind::Equals => self.parse_named_assignment(Box::new(expr), start),
-                        TokenKind::Semicolon => {
-                            let end: (usize, usize) = self.expect_token(&TokenKind::Semicolon)?.end;
-                            Ok(Spanned {
-                                node: Statement::Expression(expr),
-                                span: Span { start, end },
-                            })
-                        }
-                        _ => Err(format!(
-                            "Unexpected token after 'self' expression at {}:{}",
-                            self.peek()?.start.0,
-                            self.peek()?.start.1
-                        )),
-                    }
+                    self.parse_assignment_or_expression()
                 }
                 Keyword::SelfType => {
-                    let token: Token = self
-                        .expect_token(&TokenKind::Keyword(Keyword::SelfType))?
-                        .clone();
-
                     if self.inside_class.is_none() {
-                        return Err(format!(
-                            "Illegal use of 'Self' outside class at {}:{}",
-                            token.start.0, token.start.1
-                        ));
+                        return Err(
+                            self.error(ParseErrorType::SelfOutsideClass(String::from("Self")))
+                        );
                     }
+                    self.expect_token(&TokenKind::Keyword(Keyword::SelfType))?;
 
                     if matches!(self.peek()?.kind, TokenKind::Identifier(_)) {
-                        self.advance();
-                        let next_token: Token = self.peek()?.clone();
+                        let next_index: usize = self.index + 1;
 
-                        self.index -= 2;
-                        match next_token.kind {
+                        self.index -= 1;
+                        match self.token_at(next_index).kind {
                             TokenKind::LeftParen => self.parse_function_declaration(),
                             TokenKind::Semicolon => self.parse_field_declaration(),
-                            _ => Err(format!(
-                                "Expected '(' or ';' after identifier at {}:{}",
-                                next_token.start.0, next_token.start.1
+                            _ => Err(ParseError::unexpected(
+                                self.token_at(next_index),
+                                &["(", ";"],
                             )),
                         }
                     } else {
-                        Err(format!(
-                            "Expected identifier after 'Self' at {}:{}",
-                            token.start.0, token.start.1
-                        ))
+                        Err(self.unexpected(&["identifier"]))
                     }
                 }
                 Keyword::Static => {
                     if self.inside_class.is_none() {
-                        return Err(format!(
-                            "The 'static' keyword can only be used inside a class at {}:{}",
-                            self.peek()?.start.0,
-                            self.peek()?.start.1
-                        ));
+                        return Err(self.error(ParseErrorType::IllegalStatic));
                     }
 
-                    let token: Token = self
-                        .expect_token(&TokenKind::Keyword(Keyword::Static))?
-                        .clone();
+                    let static_index: usize = self.index;
+                    self.expect_token(&TokenKind::Keyword(Keyword::Static))?;
 
                     self.inside_static = true;
                     let stmt: Stmt = self.parse_statement()?;
@@ -420,13 +431,13 @@ impl Parser {
                         | Statement::FieldDeclaration { .. } => Ok(Spanned {
                             node: stmt.node,
                             span: Span {
-                                start: token.start,
+                                start: self.token_at(static_index).start,
                                 end: stmt.span.end,
                             },
                         }),
-                        _ => Err(format!(
-                            "The 'static' keyword can only be used on method and {}{}:{}",
-                            "field declarations at ", token.start.0, token.start.1
+                        _ => Err(ParseError::new(
+                            ParseErrorType::IllegalStatic,
+                            self.token_at(static_index),
                         )),
                     }
                 }
@@ -435,19 +446,14 @@ impl Parser {
         }
     }
 
-    fn parse_if_statement(&mut self) -> Result<Stmt, String> {
+    fn parse_if_statement(&mut self) -> Result<Stmt, ParseError> {
         if !self.outside_global_scope {
-            return Err(format!(
-                "The 'if' keyword cannot be used in the global scope at {}:{}",
-                self.peek()?.start.0,
-                self.peek()?.start.1
-            ));
+            return Err(self.error(ParseErrorType::IllegalInGlobalScope(String::from("'if'"))));
         }
 
         let mut conditional_branches: Vec<(Expr, Vec<Stmt>)> = Vec::new();
         let mut else_branch: Option<Vec<Stmt>> = None;
-        let if_token: Token = self.expect_token(&TokenKind::Keyword(Keyword::If))?.clone();
-        let start: (usize, usize) = if_token.start;
+        let start: (usize, usize) = self.expect_token(&TokenKind::Keyword(Keyword::If))?.start;
         let mut end: (usize, usize);
 
         loop {
@@ -460,7 +466,7 @@ impl Parser {
             while !self.match_token(&TokenKind::RightBrace) {
                 body.push(self.parse_statement()?);
             }
-            end = self.expect_token(&TokenKind::RightBrace)?.clone().end;
+            end = self.expect_token(&TokenKind::RightBrace)?.end;
 
             conditional_branches.push((
                 Spanned {
@@ -500,7 +506,7 @@ impl Parser {
         while !self.match_token(&TokenKind::RightBrace) {
             body.push(self.parse_statement()?);
         }
-        end = self.expect_token(&TokenKind::RightBrace)?.clone().end;
+        end = self.expect_token(&TokenKind::RightBrace)?.end;
         else_branch = Some(body);
 
         Ok(Spanned {
@@ -512,19 +518,18 @@ impl Parser {
         })
     }
 
-    fn parse_while_loop(&mut self) -> Result<Stmt, String> {
+    fn parse_while_loop(&mut self) -> Result<Stmt, ParseError> {
         if !self.outside_global_scope {
-            return Err(format!(
-                "The 'while' keyword cannot be used in the global scope at {}:{}",
-                self.peek()?.start.0,
-                self.peek()?.start.1
-            ));
+            return Err(
+                self.error(ParseErrorType::IllegalInGlobalScope(String::from(
+                    "'while'",
+                ))),
+            );
         }
 
-        let while_loop: Token = self
+        let start: (usize, usize) = self
             .expect_token(&TokenKind::Keyword(Keyword::While))?
-            .clone();
-        let start: (usize, usize) = while_loop.start;
+            .start;
 
         let cond_start: (usize, usize) = self.expect_token(&TokenKind::LeftParen)?.start;
         let condition: Expr = self.parse_expression()?;
@@ -536,7 +541,7 @@ impl Parser {
             body.push(self.parse_statement()?);
         }
 
-        let end: (usize, usize) = self.expect_token(&TokenKind::RightBrace)?.clone().end;
+        let end: (usize, usize) = self.expect_token(&TokenKind::RightBrace)?.end;
 
         Ok(Spanned {
             node: Statement::While {
@@ -553,27 +558,140 @@ impl Parser {
         })
     }
 
-    fn parse_class_declaration(&mut self) -> Result<Stmt, String> {
-        if self.outside_global_scope {
-            return Err(format!(
-                "Class declarations are only allowed in the global scope at {}:{}",
-                self.peek()?.start.0,
-                self.peek()?.start.1
+    fn parse_do_while_loop(&mut self) -> Result<Stmt, ParseError> {
+        if !self.outside_global_scope {
+            return Err(self.error(ParseErrorType::IllegalInGlobalScope(String::from("'do'"))));
+        }
+
+        let start: (usize, usize) = self.expect_token(&TokenKind::Keyword(Keyword::Do))?.start;
+
+        self.expect_token(&TokenKind::LeftBrace)?;
+        let mut body: Vec<Stmt> = Vec::new();
+        while !self.match_token(&TokenKind::RightBrace) {
+            body.push(self.parse_statement()?);
+        }
+        self.expect_token(&TokenKind::RightBrace)?;
+
+        self.expect_token(&TokenKind::Keyword(Keyword::While))?;
+        let cond_start: (usize, usize) = self.expect_token(&TokenKind::LeftParen)?.start;
+        let condition: Expr = self.parse_expression()?;
+        let cond_end: (usize, usize) = self.expect_token(&TokenKind::RightParen)?.end;
+        let end: (usize, usize) = self.expect_token(&TokenKind::Semicolon)?.end;
+
+        Ok(Spanned {
+            node: Statement::DoWhile {
+                body,
+                condition: Spanned {
+                    node: condition.node,
+                    span: Span {
+                        start: cond_start,
+                        end: cond_end,
+                    },
+                },
+            },
+            span: Span { start, end },
+        })
+    }
+
+    fn parse_loop_control(&mut self) -> Result<Stmt, ParseError> {
+        let token: Token = self.peek()?.clone();
+        self.advance();
+        let (node, keyword): (Statement, &str) = match token.kind {
+            TokenKind::Keyword(Keyword::Break) => (Statement::Break, "'break'"),
+            _ => (Statement::Continue, "'continue'"),
+        };
+
+        if !self.outside_global_scope {
+            return Err(ParseError::new(
+                ParseErrorType::IllegalInGlobalScope(String::from(keyword)),
+                &token,
             ));
         }
 
-        let class_token: Token = self
+        let end: (usize, usize) = self.expect_token(&TokenKind::Semicolon)?.end;
+        Ok(Spanned {
+            node,
+            span: Span {
+                start: token.start,
+                end,
+            },
+        })
+    }
+
+    fn parse_try_statement(&mut self) -> Result<Stmt, ParseError> {
+        if !self.outside_global_scope {
+            return Err(self.error(ParseErrorType::IllegalInGlobalScope(String::from("'try'"))));
+        }
+
+        let start: (usize, usize) = self.expect_token(&TokenKind::Keyword(Keyword::Try))?.start;
+
+        self.expect_token(&TokenKind::LeftBrace)?;
+        let mut body: Vec<Stmt> = Vec::new();
+        while !self.match_token(&TokenKind::RightBrace) {
+            body.push(self.parse_statement()?);
+        }
+        self.expect_token(&TokenKind::RightBrace)?;
+
+        self.expect_token(&TokenKind::Keyword(Keyword::Catch))?;
+        self.expect_token(&TokenKind::LeftParen)?;
+        let error_name: String = self.expect_identifier()?;
+        self.expect_token(&TokenKind::RightParen)?;
+
+        self.expect_token(&TokenKind::LeftBrace)?;
+        let mut handler: Vec<Stmt> = Vec::new();
+        while !self.match_token(&TokenKind::RightBrace) {
+            handler.push(self.parse_statement()?);
+        }
+        let end: (usize, usize) = self.expect_token(&TokenKind::RightBrace)?.end;
+
+        Ok(Spanned {
+            node: Statement::Try {
+                body,
+                error_name,
+                handler,
+            },
+            span: Span { start, end },
+        })
+    }
+
+    fn parse_throw_statement(&mut self) -> Result<Stmt, ParseError> {
+        if !self.outside_global_scope {
+            return Err(
+                self.error(ParseErrorType::IllegalInGlobalScope(String::from(
+                    "'throw'",
+                ))),
+            );
+        }
+
+        let start: (usize, usize) = self
+            .expect_token(&TokenKind::Keyword(Keyword::Throw))?
+            .start;
+        let message: Expr = self.parse_expression()?;
+        let end: (usize, usize) = self.expect_token(&TokenKind::Semicolon)?.end;
+
+        Ok(Spanned {
+            node: Statement::Throw(message),
+            span: Span { start, end },
+        })
+    }
+
+    fn parse_class_declaration(&mut self) -> Result<Stmt, ParseError> {
+        if self.outside_global_scope {
+            return Err(self.error(ParseErrorType::IllegalClassDeclaration));
+        }
+
+        let start: (usize, usize) = self
             .expect_token(&TokenKind::Keyword(Keyword::Class))?
-            .clone();
-        let start: (usize, usize) = class_token.start;
+            .start;
 
-        let identifier: String = match self
-            .expect_token_kind(&TokenKind::Identifier(String::new()))?
-            .kind
-            .clone()
-        {
-            TokenKind::Identifier(name) => name,
-            _ => unreachable!(),
+        let identifier: String = self.expect_identifier()?;
+        let generics: Vec<String> = self.parse_generic_parameters()?;
+
+        let superclass: Option<String> = if self.match_token(&TokenKind::Colon) {
+            self.advance();
+            Some(self.expect_identifier()?)
+        } else {
+            None
         };
 
         self.expect_token(&TokenKind::LeftBrace)?;
@@ -596,62 +714,240 @@ impl Parser {
             node: Statement::ClassDeclaration {
                 name: identifier,
                 body,
+                attributes: Vec::new(),
+                generics,
+                superclass,
             },
             span: Span { start, end },
         })
     }
 
-    fn parse_variable_declaration(&mut self) -> Result<Stmt, String> {
-        if self.inside_class.is_some() && !self.inside_method {
-            return self.parse_field_declaration();
-        } else if !self.outside_global_scope {
-            return Err(format!(
-                "Variable declarations are not allowed in the global scope at {}:{}",
-                self.peek()?.start.0,
-                self.peek()?.start.1
-            ));
+    fn parse_enum_declaration(&mut self) -> Result<Stmt, ParseError> {
+        if self.outside_global_scope {
+            return Err(self.error(ParseErrorType::IllegalEnumDeclaration));
         }
-        let token: Token = self.peek()?.clone();
-        let type_: String = match &token.kind {
-            TokenKind::Identifier(name) => name.clone(),
-            _ => unreachable!(),
-        };
-        let start: (usize, usize) = token.start;
-        self.advance();
 
-        let name: String = match &self.peek()?.kind {
-            TokenKind::Identifier(name) => name.clone(),
-            _ => unreachable!(),
-        };
-        self.advance();
+        let start: (usize, usize) = self.expect_token(&TokenKind::Keyword(Keyword::Enum))?.start;
 
-        let value: Option<Expr> = if self.match_token(&TokenKind::Equals) {
-            self.expect_token(&TokenKind::Equals)?;
-            Some(self.parse_expression()?)
-        } else {
-            None
-        };
+        let name: String = self.expect_identifier()?;
 
-        let end: (usize, usize) = self.expect_token(&TokenKind::Semicolon)?.end;
-        Ok(Spanned {
-            node: Statement::VariableDeclaration { type_, name, value },
-            span: Span { start, end },
-        })
-    }
+        self.expect_token(&TokenKind::LeftBrace)?;
 
-    fn parse_field_declaration(&mut self) -> Result<Stmt, String> {
-        let token: Token = self.peek()?.clone();
-        let type_: String = match &token.kind {
-            TokenKind::Identifier(name) => name.clone(),
-            TokenKind::Keyword(Keyword::SelfType) => {
-                self.inside_class.as_ref().expect("checked before").into()
-            }
-            _ => unreachable!(),
-        };
-        let start: (usize, usize) = token.start;
-        self.advance();
+        let mut variants: Vec<EnumVariant> = Vec::new();
+        while !self.match_token(&TokenKind::RightBrace) {
+            let variant: String = self.expect_identifier()?;
 
-        let name: String = match &self.peek()?.kind {
+            let mut payload: Vec<String> = Vec::new();
+            if self.match_token(&TokenKind::LeftParen) {
+                self.advance();
+                while !self.match_token(&TokenKind::RightParen) {
+                    payload.push(self.parse_type()?);
+                    if !self.match_token(&TokenKind::RightParen) {
+                        self.expect_token(&TokenKind::Comma)?;
+                    }
+                }
+                self.expect_token(&TokenKind::RightParen)?;
+            }
+
+            variants.push(EnumVariant {
+                name: variant,
+                payload,
+            });
+
+            if !self.match_token(&TokenKind::RightBrace) {
+                self.expect_token(&TokenKind::Comma)?;
+            }
+        }
+        let end: (usize, usize) = self.expect_token(&TokenKind::RightBrace)?.end;
+
+        Ok(Spanned {
+            node: Statement::EnumDeclaration { name, variants },
+            span: Span { start, end },
+        })
+    }
+
+    fn parse_interface_declaration(&mut self) -> Result<Stmt, ParseError> {
+        if self.outside_global_scope {
+            return Err(self.error(ParseErrorType::IllegalInterfaceDeclaration));
+        }
+
+        let start: (usize, usize) = self
+            .expect_token(&TokenKind::Keyword(Keyword::Interface))?
+            .start;
+
+        let name: String = self.expect_identifier()?;
+
+        self.expect_token(&TokenKind::LeftBrace)?;
+
+        let mut methods: Vec<MethodSignature> = Vec::new();
+        while !self.match_token(&TokenKind::RightBrace) {
+            let return_type: String = self.parse_type()?;
+
+            let method: String = self.expect_identifier()?;
+
+            self.expect_token(&TokenKind::LeftParen)?;
+            let parameters: Vec<Parameter> =
+                self.parse_function_declaration_parameters(&TokenKind::RightParen)?;
+            self.expect_token(&TokenKind::RightParen)?;
+            self.expect_token(&TokenKind::Semicolon)?;
+
+            methods.push(MethodSignature {
+                return_type,
+                name: method,
+                parameters,
+            });
+        }
+        let end: (usize, usize) = self.expect_token(&TokenKind::RightBrace)?.end;
+
+        Ok(Spanned {
+            node: Statement::InterfaceDeclaration { name, methods },
+            span: Span { start, end },
+        })
+    }
+
+    fn parse_variable_declaration(&mut self) -> Result<Stmt, ParseError> {
+        if self.inside_class.is_some() && !self.inside_method {
+            return self.parse_field_declaration();
+        } else if !self.outside_global_scope {
+            return Err(
+                self.error(ParseErrorType::IllegalInGlobalScope(String::from(
+                    "Variable declaration",
+                ))),
+            );
+        }
+        let start: (usize, usize) = self.peek()?.start;
+        let type_: String = self.parse_type()?;
+
+        let name: String = match &self.peek()?.kind {
+            TokenKind::Identifier(name) => name.clone(),
+            _ => unreachable!(),
+        };
+        self.advance();
+
+        let value: Option<Expr> = if self.match_token(&TokenKind::Equals) {
+            self.expect_token(&TokenKind::Equals)?;
+            Some(self.parse_expression()?)
+        } else {
+            None
+        };
+
+        let end: (usize, usize) = self.expect_token(&TokenKind::Semicolon)?.end;
+        Ok(Spanned {
+            node: Statement::VariableDeclaration { type_, name, value },
+            span: Span { start, end },
+        })
+    }
+
+    fn parse_let_declaration(&mut self) -> Result<Stmt, ParseError> {
+        if !self.outside_global_scope || (self.inside_class.is_some() && !self.inside_method) {
+            return Err(self.error(ParseErrorType::IllegalVariableDeclaration));
+        }
+
+        let start: (usize, usize) = self.expect_token(&TokenKind::Keyword(Keyword::Let))?.start;
+
+        if let TokenKind::Identifier(name) = &self.peek()?.kind {
+            let name: String = name.clone();
+            self.advance();
+
+            let type_: String = if self.match_token(&TokenKind::Colon) {
+                self.advance();
+                self.parse_type()?
+            } else {
+                String::new()
+            };
+
+            let value: Option<Expr> = if self.match_token(&TokenKind::Equals) {
+                self.advance();
+                Some(self.parse_expression()?)
+            } else {
+                None
+            };
+
+            let end: (usize, usize) = self.expect_token(&TokenKind::Semicolon)?.end;
+            return Ok(Spanned {
+                node: Statement::VariableDeclaration { type_, name, value },
+                span: Span { start, end },
+            });
+        }
+
+        self.expect_token(&TokenKind::LeftParen)?;
+
+        let mut names: Vec<String> = Vec::new();
+        loop {
+            let name: String = self.expect_identifier()?;
+            names.push(name);
+
+            if self.match_token(&TokenKind::RightParen) {
+                break;
+            }
+            self.expect_token(&TokenKind::Comma)?;
+        }
+
+        self.expect_token(&TokenKind::RightParen)?;
+        self.expect_token(&TokenKind::Equals)?;
+        let value: Expr = self.parse_expression()?;
+        let end: (usize, usize) = self.expect_token(&TokenKind::Semicolon)?.end;
+
+        Ok(Spanned {
+            node: Statement::DestructuringDeclaration { names, value },
+            span: Span { start, end },
+        })
+    }
+
+    fn parse_const_declaration(&mut self) -> Result<Stmt, ParseError> {
+        if !self.outside_global_scope {
+            return Err(
+                self.error(ParseErrorType::IllegalInGlobalScope(String::from(
+                    "Const declaration",
+                ))),
+            );
+        }
+
+        let start: (usize, usize) = self
+            .expect_token(&TokenKind::Keyword(Keyword::Const))?
+            .start;
+        let type_: String = self.parse_type()?;
+
+        let name: String = self.expect_identifier()?;
+
+        self.expect_token(&TokenKind::Equals)?;
+        let value: Expr = self.parse_expression()?;
+        let end: (usize, usize) = self.expect_token(&TokenKind::Semicolon)?.end;
+
+        Ok(Spanned {
+            node: Statement::ConstDeclaration { type_, name, value },
+            span: Span { start, end },
+        })
+    }
+
+    fn parse_import(&mut self) -> Result<Stmt, ParseError> {
+        if self.outside_global_scope {
+            return Err(self.error(ParseErrorType::IllegalImport));
+        }
+
+        let start: (usize, usize) = self
+            .expect_token(&TokenKind::Keyword(Keyword::Import))?
+            .start;
+
+        let path: String = match &self.peek()?.kind {
+            TokenKind::String(path) => path.clone(),
+            _ => return Err(self.unexpected(&["string"])),
+        };
+        self.advance();
+
+        let end: (usize, usize) = self.expect_token(&TokenKind::Semicolon)?.end;
+
+        Ok(Spanned {
+            node: Statement::Import(path),
+            span: Span { start, end },
+        })
+    }
+
+    fn parse_field_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let start: (usize, usize) = self.peek()?.start;
+        let type_: String = self.parse_type()?;
+
+        let name: String = match &self.peek()?.kind {
             TokenKind::Identifier(name) => name.clone(),
             _ => unreachable!(),
         };
@@ -687,17 +983,9 @@ impl Parser {
         }
     }
 
-    fn parse_function_declaration(&mut self) -> Result<Stmt, String> {
-        let token: Token = self.peek()?.clone();
-
-        let mut return_type: String = match &token.kind {
-            TokenKind::Identifier(name) => name.clone(),
-            TokenKind::Keyword(Keyword::SelfType) => {
-                self.inside_class.as_ref().expect("checked before").into()
-            }
-            _ => unreachable!(),
-        };
-        self.advance();
+    fn parse_function_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let start: (usize, usize) = self.peek()?.start;
+        let mut return_type: String = self.parse_type()?;
 
         let name: String = match &self.peek()?.kind {
             TokenKind::Identifier(name) => name.clone(),
@@ -714,9 +1002,11 @@ impl Parser {
         } else {
             false
         };
+        let generics: Vec<String> = self.parse_generic_parameters()?;
 
         self.expect_token(&TokenKind::LeftParen)?;
-        let parameters: Vec<(String, String)> = self.parse_function_declaration_parameters()?;
+        let parameters: Vec<Parameter> =
+            self.parse_function_declaration_parameters(&TokenKind::RightParen)?;
         self.expect_token(&TokenKind::RightParen)?;
 
         self.expect_token(&TokenKind::LeftBrace)?;
@@ -725,33 +1015,30 @@ impl Parser {
         self.outside_global_scope = true;
         self.inside_method = self.inside_class.is_some();
 
-        let mut body: Vec<Stmt> = Vec::new();
-        while !self.match_token(&TokenKind::RightBrace) {
-            body.push(self.parse_statement()?);
-        }
+        let implicit_return: bool = !return_type.is_empty() && return_type != "void";
+        let body: Vec<Stmt> = self.parse_function_body(implicit_return)?;
 
         let end: (usize, usize) = self.expect_token(&TokenKind::RightBrace)?.end;
 
         self.outside_global_scope = outside_global_scope_backup;
         self.inside_method = false;
 
-        if self.inside_class.is_some() {
+        if let Some(class_name) = &self.inside_class {
             Ok(Spanned {
                 node: Statement::MethodDeclaration {
                     return_type,
                     name: if constructor {
-                        self.inside_class.as_ref().expect("checked before").clone()
+                        class_name.clone()
                     } else {
                         name
                     },
                     parameters,
                     body,
                     static_: self.inside_static && !constructor,
+                    attributes: Vec::new(),
+                    generics,
                 },
-                span: Span {
-                    start: token.start,
-                    end,
-                },
+                span: Span { start, end },
             })
         } else {
             Ok(Spanned {
@@ -760,50 +1047,122 @@ impl Parser {
                     name,
                     parameters,
                     body,
+                    attributes: Vec::new(),
+                    generics,
                 },
-                span: Span {
-                    start: token.start,
-                    end,
-                },
+                span: Span { start, end },
             })
         }
     }
 
-    fn parse_function_declaration_parameters(&mut self) -> Result<Vec<(String, String)>, String> {
-        let mut parameters: Vec<(String, String)> = Vec::new();
+    /// Parses the statements of a function, method or lambda body up to its closing brace. A
+    /// final expression without a trailing semicolon is the value of the body and becomes an
+    /// implicit return if `implicit_return` is set, or a plain expression statement otherwise.
+    ///
+    /// Only function, method and lambda bodies have a value. The blocks of `if`, `while` and
+    /// other statements are parsed as plain statement lists, because those statements are not
+    /// expressions and a final expression inside them has nothing to become the value of.
+    fn parse_function_body(&mut self, implicit_return: bool) -> Result<Vec<Stmt>, ParseError> {
+        let mut body: Vec<Stmt> = Vec::new();
+
+        while !self.match_token(&TokenKind::RightBrace) {
+            let checkpoint: usize = self.index;
+            match self.parse_statement() {
+                Ok(stmt) => body.push(stmt),
+                Err(error) => {
+                    // A trailing expression only fails as a statement because its semicolon is
+                    // missing before the closing brace, every other error is reported as is
+                    if !matches!(
+                        error.error_type,
+                        ParseErrorType::UnexpectedToken {
+                            found: TokenKind::RightBrace,
+                            ..
+                        }
+                    ) {
+                        return Err(error);
+                    }
+
+                    self.index = checkpoint;
+                    let Ok(expr) = self.parse_expression() else {
+                        return Err(error);
+                    };
+                    if !self.match_token(&TokenKind::RightBrace)
+                        || self.peek()?.start != error.span.start
+                    {
+                        return Err(error);
+                    }
+
+                    let span: Span = expr.span.clone();
+                    body.push(Spanned {
+                        node: if implicit_return {
+                            Statement::Return(Some(expr))
+                        } else {
+                            Statement::Expression(expr)
+                        },
+                        span,
+                    });
+                }
+            }
+        }
+
+        Ok(body)
+    }
+
+    /// Parses an optional list of generic type parameters, e.g. `<K, V>`.
+    fn parse_generic_parameters(&mut self) -> Result<Vec<String>, ParseError> {
+        let mut generics: Vec<String> = Vec::new();
+        if !self.match_token(&TokenKind::LeftAngle) {
+            return Ok(generics);
+        }
+        self.advance();
 
         loop {
-            if self.match_token(&TokenKind::RightParen) {
+            let name: String = self.expect_identifier()?;
+            generics.push(name);
+
+            if self.match_token(&TokenKind::RightAngle) {
                 break;
             }
+            self.expect_token(&TokenKind::Comma)?;
+        }
+        self.expect_token(&TokenKind::RightAngle)?;
 
-            let identifier: &Token =
-                self.expect_token_kind(&TokenKind::Identifier(String::new()))?;
-            let TokenKind::Identifier(type_) = &identifier.kind.clone() else {
-                unreachable!()
-            };
+        Ok(generics)
+    }
 
-            let identifier: &Token =
-                self.expect_token_kind(&TokenKind::Identifier(String::new()))?;
-            let TokenKind::Identifier(name) = &identifier.kind else {
-                unreachable!()
-            };
+    fn parse_function_declaration_parameters(
+        &mut self,
+        closing: &TokenKind,
+    ) -> Result<Vec<Parameter>, ParseError> {
+        let mut parameters: Vec<Parameter> = Vec::new();
 
-            parameters.push((type_.clone(), name.clone()));
+        loop {
+            if self.match_token(closing) {
+                break;
+            }
+
+            let start: (usize, usize) = self.peek()?.start;
+            let type_: String = self.parse_type()?;
+
+            let end: (usize, usize) = self.peek()?.end;
+            let name: String = self.expect_identifier()?;
+
+            parameters.push(Parameter {
+                type_,
+                name,
+                span: Span { start, end },
+            });
 
             let peek: &Token = self.peek()?;
-            match peek.kind {
+            match &peek.kind {
                 TokenKind::Comma => {
                     self.advance();
                 }
-                TokenKind::RightParen => {
+                kind if kind == closing => {
                     break;
                 }
                 _ => {
-                    return Err(format!(
-                        "Expected ',' or ')', found '{:?}' at {}:{}",
-                        peek.kind, peek.start.0, peek.start.1
-                    ));
+                    return Err(self.unexpected(&[",", &closing.to_string()]));
                 }
             }
         }
@@ -815,22 +1174,30 @@ impl Parser {
         &mut self,
         name: Box<Expr>,
         start: (usize, usize),
-    ) -> Result<Stmt, String> {
+    ) -> Result<Stmt, ParseError> {
         if !self.outside_global_scope {
-            return Err(format!(
-                "Assignments are not allowed in the global scope at {}:{}",
-                self.peek()?.start.0,
-                self.peek()?.start.1
-            ));
+            return Err(
+                self.error(ParseErrorType::IllegalInGlobalScope(String::from(
+                    "Assignment",
+                ))),
+            );
+        }
+
+        if !matches!(
+            name.node,
+            Expression::Identifier(_) | Expression::MemberAccess { .. } | Expression::Index { .. }
+        ) {
+            return Err(ParseError {
+                error_type: ParseErrorType::InvalidAssignmentTarget(String::from(name.node.name())),
+                span: name.span,
+            });
         }
 
         self.expect_token(&TokenKind::Equals)?;
 
         let value: Expr = self.parse_expression()?;
 
-        self.expect_token(&TokenKind::Semicolon)?;
-
-        let end: (usize, usize) = value.span.end;
+        let end: (usize, usize) = self.expect_token(&TokenKind::Semicolon)?.end;
 
         Ok(Spanned {
             node: Statement::Assignment {
@@ -841,94 +1208,117 @@ impl Parser {
         })
     }
 
-    fn parse_assignment(&mut self) -> Result<Stmt, String> {
+    /// Parses `x++;` or `x--;`, desugared to `x = x + 1;` or `x = x - 1;`.
+    fn parse_increment(
+        &mut self,
+        name: Box<Expr>,
+        start: (usize, usize),
+    ) -> Result<Stmt, ParseError> {
         if !self.outside_global_scope {
-            return Err(format!(
-                "Assignments are not allowed in the global scope at {}:{}",
-                self.peek()?.start.0,
-                self.peek()?.start.1
-            ));
+            return Err(
+                self.error(ParseErrorType::IllegalInGlobalScope(String::from(
+                    "Assignment",
+                ))),
+            );
         }
 
-        let token: Token = self.peek()?.clone();
+        if !matches!(
+            name.node,
+            Expression::Identifier(_) | Expression::MemberAccess { .. } | Expression::Index { .. }
+        ) {
+            return Err(ParseError {
+                error_type: ParseErrorType::InvalidAssignmentTarget(String::from(name.node.name())),
+                span: name.span,
+            });
+        }
+
+        let op_token: &Token = self.peek()?;
+        let op_span: Span = Span {
+            start: op_token.start,
+            end: op_token.end,
+        };
+        let operator: BinaryOperator = match op_token.kind {
+            TokenKind::PlusPlus => BinaryOperator::Add,
+            TokenKind::MinusMinus => BinaryOperator::Subtract,
+            _ => return Err(self.unexpected(&["++", "--"])),
+        };
         self.advance();
-        self.parse_named_assignment(
-            Box::new(Spanned {
-                node: Expression::Identifier(match &token.kind {
-                    TokenKind::Identifier(name) => name.clone(),
-                    x => unreachable!("Expected identifier, found {:?}", x),
+
+        let end: (usize, usize) = self.expect_token(&TokenKind::Semicolon)?.end;
+
+        let value: Expr = Spanned {
+            node: Expression::Binary {
+                left: name.clone(),
+                operator,
+                right: Box::new(Spanned {
+                    node: Expression::Literal(Literal::Integer(1)),
+                    span: op_span.clone(),
                 }),
-                span: Span {
-                    start: token.start,
-                    end: token.end,
-                },
-            }),
-            token.start,
-        )
+            },
+            span: Span {
+                start,
+                end: op_span.end,
+            },
+        };
+
+        Ok(Spanned {
+            node: Statement::Assignment {
+                assignee: name,
+                value,
+            },
+            span: Span { start, end },
+        })
     }
 
-    fn parse_expression(&mut self) -> Result<Expr, String> {
+    fn parse_expression(&mut self) -> Result<Expr, ParseError> {
         if !self.outside_global_scope {
-            return Err(format!(
-                "Expressions are not allowed in the global scope at {}:{}",
-                self.peek()?.start.0,
-                self.peek()?.start.1
-            ));
+            return Err(
+                self.error(ParseErrorType::IllegalInGlobalScope(String::from(
+                    "Expression",
+                ))),
+            );
         }
 
-        self.parse_precedence(0, false)
+        self.parse_precedence(0, None)
     }
 
-    fn operator_precedence(kind: &TokenKind) -> Option<u8> {
-        match kind {
-            TokenKind::Asterisk | TokenKind::Slash => Some(5),
-            TokenKind::Plus | TokenKind::Minus => Some(4),
-            _ if Self::COMPARISON_TOKEN.contains(kind) => Some(3),
-            TokenKind::And => Some(2),
-            TokenKind::Or => Some(1),
-            _ => None,
-        }
-    }
-
-    fn parse_precedence(&mut self, min_prec: u8, seen_comparison: bool) -> Result<Expr, String> {
+    /// Parses a chain of binary operators binding at least as tightly as `min_prec`, following
+    /// the [`crate::types::BINARY_OPERATORS`] table. `non_associative` holds the precedence of the
+    /// non-associative operator this is the right operand of, which must not be chained with
+    /// another operator of the same precedence.
+    fn parse_precedence(
+        &mut self,
+        min_prec: u8,
+        non_associative: Option<u8>,
+    ) -> Result<Expr, ParseError> {
         let mut left: Expr = self.parse_unary()?;
+        let mut chained: Option<u8> = non_associative;
 
-        while let Ok(next) = self.peek() {
-            let op_token: Token = next.clone();
-            let is_comparison_op: bool = Self::COMPARISON_TOKEN.contains(&op_token.kind);
+        while let Ok(op_token) = self.peek() {
+            let Some(info) = BinaryOperator::from_token(&op_token.kind) else {
+                break;
+            };
 
-            if seen_comparison && is_comparison_op {
-                return Err(format!(
-                    "Chained comparison operators are not allowed ({}:{})",
-                    op_token.start.0, op_token.start.1
-                ));
+            if info.associativity == Associativity::NonAssociative
+                && chained == Some(info.precedence)
+            {
+                return Err(ParseError::new(ParseErrorType::ChainedComparison, op_token));
             }
 
-            let prec: u8 = match Self::operator_precedence(&op_token.kind) {
-                Some(p) if p >= min_prec => p,
-                _ => break,
-            };
+            if info.precedence < min_prec {
+                break;
+            }
 
             self.advance();
 
-            let right: Expr =
-                self.parse_precedence(prec + 1, seen_comparison || is_comparison_op)?;
-
-            let operator: BinaryOperator = match op_token.kind {
-                TokenKind::Plus => BinaryOperator::Add,
-                TokenKind::Minus => BinaryOperator::Subtract,
-                TokenKind::Asterisk => BinaryOperator::Multiply,
-                TokenKind::Slash => BinaryOperator::Divide,
-                TokenKind::EqualsEquals => BinaryOperator::Equals,
-                TokenKind::NotEquals => BinaryOperator::NotEquals,
-                TokenKind::LeftAngle => BinaryOperator::LessThan,
-                TokenKind::RightAngle => BinaryOperator::GreaterThan,
-                TokenKind::LessThanOrEqual => BinaryOperator::LessThanOrEqual,
-                TokenKind::GreaterThanOrEqual => BinaryOperator::GreaterThanOrEqual,
-                TokenKind::And => BinaryOperator::And,
-                TokenKind::Or => BinaryOperator::Or,
-                _ => unreachable!(),
+            let non_associative: Option<u8> =
+                (info.associativity == Associativity::NonAssociative).then_some(info.precedence);
+            let right_prec: u8 = match info.associativity {
+                Associativity::Right => info.precedence,
+                Associativity::Left | Associativity::NonAssociative => info.precedence + 1,
             };
+            let right: Expr = self.parse_precedence(right_prec, non_associative)?;
+            chained = non_associative;
 
             let start: (usize, usize) = left.span.start;
             let end: (usize, usize) = right.span.end;
@@ -936,7 +1326,7 @@ impl Parser {
             left = Spanned {
                 node: Expression::Binary {
                     left: Box::new(left),
-                    operator,
+                    operator: info.operator.clone(),
                     right: Box::new(right),
                 },
                 span: Span { start, end },
@@ -946,11 +1336,12 @@ impl Parser {
         Ok(left)
     }
 
-    fn parse_unary(&mut self) -> Result<Expr, String> {
-        let token: Token = self.peek()?.clone();
+    fn parse_unary(&mut self) -> Result<Expr, ParseError> {
+        let start: (usize, usize) = self.peek()?.start;
 
-        let operator: Option<UnaryOperator> = match token.kind {
+        let operator: Option<UnaryOperator> = match self.peek()?.kind {
             TokenKind::Exclamation => Some(UnaryOperator::Not),
+            TokenKind::Minus => Some(UnaryOperator::Negate),
             _ => None,
         };
 
@@ -963,7 +1354,6 @@ impl Parser {
 
         let operand: Expr = self.parse_unary()?;
 
-        let start: (usize, usize) = token.start;
         let end: (usize, usize) = operand.span.end;
 
         Ok(Spanned {
@@ -975,17 +1365,45 @@ impl Parser {
         })
     }
 
-    fn parse_primary(&mut self) -> Result<Expr, String> {
-        let token: Token = self.peek()?.clone();
+    fn parse_primary(&mut self) -> Result<Expr, ParseError> {
+        let token: &Token = self.peek()?;
         let start: (usize, usize) = token.start;
-        match token.kind {
+
+        if matches!(token.kind, TokenKind::Pipe | TokenKind::Or) {
+            return self.parse_lambda();
+        }
+
+        let atom: Expr = self.parse_atom()?;
+        self.parse_postfix_chain(atom, start)
+    }
+
+    fn parse_atom(&mut self) -> Result<Expr, ParseError> {
+        let token: &Token = self.peek()?;
+        let start: (usize, usize) = token.start;
+        let end: (usize, usize) = token.end;
+        match &token.kind {
             TokenKind::Integer(_)
             | TokenKind::Float(_)
             | TokenKind::String(_)
-            | TokenKind::Boolean(_) => self.parse_literal(),
+            | TokenKind::Boolean(_)
+            | TokenKind::Null => self.parse_literal(),
             TokenKind::LeftParen => {
                 self.advance();
                 let expr: Expr = self.parse_expression()?;
+
+                if self.match_token(&TokenKind::Comma) {
+                    let mut elements: Vec<Expr> = vec![expr];
+                    while self.match_token(&TokenKind::Comma) {
+                        self.advance();
+                        elements.push(self.parse_expression()?);
+                    }
+                    let end: (usize, usize) = self.expect_token(&TokenKind::RightParen)?.end;
+                    return Ok(Spanned {
+                        node: Expression::Tuple(elements),
+                        span: Span { start, end },
+                    });
+                }
+
                 let end: (usize, usize) = self.expect_token(&TokenKind::RightParen)?.end;
                 Ok(Spanned {
                     node: expr.node,
@@ -993,128 +1411,248 @@ impl Parser {
                 })
             }
             TokenKind::Identifier(identifier) => {
+                let node: Expression = Expression::Identifier(identifier.clone());
                 self.advance();
-                match self.peek()?.kind {
-                    TokenKind::LeftParen => {
-                        self.expect_token(&TokenKind::LeftParen)?;
-                        return self.parse_function_call(
-                            Box::new(Spanned {
-                                node: Expression::Identifier(identifier),
-                                span: Span {
-                                    start,
-                                    end: token.end,
-                                },
-                            }),
-                            start,
-                        );
-                    }
-                    TokenKind::Dot => {
-                        return self.parse_postfix_chain(
-                            Spanned {
-                                node: Expression::Identifier(identifier),
-                                span: Span {
-                                    start,
-                                    end: token.end,
-                                },
-                            },
-                            start,
-                        );
-                    }
-                    _ => {}
-                }
                 Ok(Spanned {
-                    node: Expression::Identifier(identifier),
-                    span: Span {
-                        start,
-                        end: token.end,
-                    },
+                    node,
+                    span: Span { start, end },
                 })
             }
             TokenKind::Keyword(Keyword::Self_) => {
-                let token: Token = self
-                    .expect_token(&TokenKind::Keyword(Keyword::Self_))?
-                    .clone();
-
-                let mut expr: Expr = Spanned {
+                self.advance();
+                Ok(Spanned {
                     node: Expression::Self_,
-                    span: Span {
-                        start: token.start,
-                        end: token.end,
-                    },
-                };
-
-                if self.peek()?.kind == TokenKind::Dot {
-                    expr = self.parse_postfix_chain(expr, token.start)?;
-                }
-
-                Ok(expr)
+                    span: Span { start, end },
+                })
             }
-            _ => Err(format!(
-                "Unexpected token: '{:?}' at {}:{}",
-                token.kind, token.start.0, token.start.1
-            )),
+            TokenKind::InterpolationStart(_) => self.parse_interpolated_string(),
+            TokenKind::LeftBracket => self.parse_array_literal(),
+            TokenKind::LeftBrace => self.parse_map_literal(),
+            _ => Err(ParseError::unexpected(token, &["expression"])),
         }
     }
 
-    fn parse_literal(&mut self) -> Result<Expr, String> {
-        let token: Token = self.peek()?.clone();
-        let start: (usize, usize) = token.start;
-        let end: (usize, usize) = token.end;
-        match &token.kind {
-            TokenKind::Integer(value) => {
-                self.advance();
-                Ok(Spanned {
-                    node: Expression::Literal(Literal::Integer(*value)),
-                    span: Span { start, end },
-                })
+    /// Parses an interpolated string, desugared to a concatenation of its text parts and the
+    /// `toString()` results of its embedded expressions.
+    fn parse_interpolated_string(&mut self) -> Result<Expr, ParseError> {
+        let first: &Token = self.peek()?;
+        let start: (usize, usize) = first.start;
+        let TokenKind::InterpolationStart(text) = &first.kind else {
+            return Err(self.unexpected(&["interpolated string"]));
+        };
+        let mut result: Option<Expr> = Self::interpolation_text(text, first);
+        self.advance();
+
+        let end: (usize, usize) = loop {
+            let expr: Expr = self.parse_expression()?;
+            let span: Span = expr.span.clone();
+            let to_string: Expr = Spanned {
+                node: Expression::Call {
+                    callee: Box::new(Spanned {
+                        node: Expression::MemberAccess {
+                            object: Box::new(expr),
+                            member: String::from("toString"),
+                        },
+                        span: span.clone(),
+                    }),
+                    arguments: Vec::new(),
+                    named_arguments: Vec::new(),
+                },
+                span,
+            };
+            result = Some(Self::concatenate(result, to_string));
+
+            let token: &Token = self.peek()?;
+            let (TokenKind::InterpolationMiddle(text) | TokenKind::InterpolationEnd(text)) =
+                &token.kind
+            else {
+                return Err(self.unexpected(&["}"]));
+            };
+            let part: Option<Expr> = Self::interpolation_text(text, token);
+            let end: Option<(usize, usize)> =
+                matches!(token.kind, TokenKind::InterpolationEnd(_)).then_some(token.end);
+            self.advance();
+
+            if let Some(part) = part {
+                result = Some(Self::concatenate(result, part));
             }
-            TokenKind::Float(value) => {
-                self.advance();
-                Ok(Spanned {
-                    node: Expression::Literal(Literal::Float(*value)),
-                    span: Span { start, end },
-                })
+
+            if let Some(end) = end {
+                break end;
             }
-            TokenKind::String(value) => {
-                self.advance();
-                Ok(Spanned {
-                    node: Expression::Literal(Literal::String(value.clone())),
-                    span: Span { start, end },
-                })
+        };
+
+        let mut result: Expr = result.expect("Interpolated strings embed at least one expression");
+        result.span = Span { start, end };
+        Ok(result)
+    }
+
+    /// Returns the string literal for a non-empty text part of an interpolated string.
+    fn interpolation_text(text: &str, token: &Token) -> Option<Expr> {
+        (!text.is_empty()).then(|| Spanned {
+            node: Expression::Literal(Literal::String(String::from(text))),
+            span: Span {
+                start: token.start,
+                end: token.end,
+            },
+        })
+    }
+
+    fn concatenate(left: Option<Expr>, right: Expr) -> Expr {
+        match left {
+            Some(left) => {
+                let span: Span = Span {
+                    start: left.span.start,
+                    end: right.span.end,
+                };
+                Spanned {
+                    node: Expression::Binary {
+                        left: Box::new(left),
+                        operator: BinaryOperator::Add,
+                        right: Box::new(right),
+                    },
+                    span,
+                }
             }
-            TokenKind::Boolean(value) => {
-                self.advance();
-                Ok(Spanned {
-                    node: Expression::Literal(Literal::Boolean(*value)),
-                    span: Span { start, end },
-                })
+            None => right,
+        }
+    }
+
+    fn parse_array_literal(&mut self) -> Result<Expr, ParseError> {
+        let start: (usize, usize) = self.expect_token(&TokenKind::LeftBracket)?.start;
+        let mut elements: Vec<Expr> = Vec::new();
+
+        while !self.match_token(&TokenKind::RightBracket) {
+            elements.push(self.parse_expression()?);
+
+            if !self.match_token(&TokenKind::RightBracket) {
+                self.expect_token(&TokenKind::Comma)?;
             }
-            _ => Err(format!(
-                "Expected literal, found {:?} at {}:{}",
-                token.kind, start.0, start.1
-            )),
         }
+
+        let end: (usize, usize) = self.expect_token(&TokenKind::RightBracket)?.end;
+
+        Ok(Spanned {
+            node: Expression::ArrayLiteral(elements),
+            span: Span { start, end },
+        })
+    }
+
+    fn parse_map_literal(&mut self) -> Result<Expr, ParseError> {
+        let start: (usize, usize) = self.expect_token(&TokenKind::LeftBrace)?.start;
+        let mut entries: Vec<(Expr, Expr)> = Vec::new();
+
+        while !self.match_token(&TokenKind::RightBrace) {
+            let key: Expr = self.parse_expression()?;
+            self.expect_token(&TokenKind::Colon)?;
+            let value: Expr = self.parse_expression()?;
+            entries.push((key, value));
+
+            if !self.match_token(&TokenKind::RightBrace) {
+                self.expect_token(&TokenKind::Comma)?;
+            }
+        }
+
+        let end: (usize, usize) = self.expect_token(&TokenKind::RightBrace)?.end;
+
+        Ok(Spanned {
+            node: Expression::MapLiteral(entries),
+            span: Span { start, end },
+        })
+    }
+
+    /// Parses a lambda, e.g. `|int x| x * 2` or `|| { ... }`. Its parameters are declared like
+    /// function parameters and need a type: the language infers the types of declarations from
+    /// their values only, and a parameter has no value to infer it from.
+    fn parse_lambda(&mut self) -> Result<Expr, ParseError> {
+        let start: (usize, usize) = self.peek()?.start;
+
+        let parameters: Vec<Parameter> = if self.match_token(&TokenKind::Or) {
+            self.advance();
+            Vec::new()
+        } else {
+            self.expect_token(&TokenKind::Pipe)?;
+            let parameters: Vec<Parameter> =
+                self.parse_function_declaration_parameters(&TokenKind::Pipe)?;
+            self.expect_token(&TokenKind::Pipe)?;
+            parameters
+        };
+
+        let (body, end): (Vec<Stmt>, (usize, usize)) = if self.match_token(&TokenKind::LeftBrace) {
+            self.advance();
+            let body: Vec<Stmt> = self.parse_function_body(true)?;
+            (body, self.expect_token(&TokenKind::RightBrace)?.end)
+        } else {
+            let expr: Expr = self.parse_expression()?;
+            let span: Span = expr.span.clone();
+            let end: (usize, usize) = span.end;
+            (
+                vec![Spanned {
+                    node: Statement::Return(Some(expr)),
+                    span,
+                }],
+                end,
+            )
+        };
+
+        Ok(Spanned {
+            node: Expression::Lambda { parameters, body },
+            span: Span { start, end },
+        })
+    }
+
+    fn parse_literal(&mut self) -> Result<Expr, ParseError> {
+        let token: &Token = self.peek()?;
+        let span: Span = Span {
+            start: token.start,
+            end: token.end,
+        };
+        let literal: Literal = match &token.kind {
+            TokenKind::Integer(value) => Literal::Integer(*value),
+            TokenKind::Float(value) => Literal::Float(*value),
+            TokenKind::String(value) => Literal::String(value.clone()),
+            TokenKind::Boolean(value) => Literal::Boolean(*value),
+            TokenKind::Null => Literal::Null,
+            _ => return Err(ParseError::unexpected(token, &["literal"])),
+        };
+        self.advance();
+
+        Ok(Spanned {
+            node: Expression::Literal(literal),
+            span,
+        })
     }
 
     fn parse_function_call(
         &mut self,
         callee: Box<Expr>,
         start: (usize, usize),
-    ) -> Result<Expr, String> {
+    ) -> Result<Expr, ParseError> {
         if !self.outside_global_scope {
-            return Err(format!(
-                "Expressions are not allowed in the global scope at {}:{}",
-                self.peek()?.start.0,
-                self.peek()?.start.1
-            ));
+            return Err(
+                self.error(ParseErrorType::IllegalInGlobalScope(String::from(
+                    "Expression",
+                ))),
+            );
         }
 
         let mut arguments: Vec<Expr> = Vec::new();
+        let mut named_arguments: Vec<(String, Expr)> = Vec::new();
 
         if !self.match_token(&TokenKind::RightParen) {
             loop {
-                let value: Expr = self.parse_expression()?;
-                arguments.push(value);
+                if self.check_next_tokens(&[TokenKind::Identifier(String::new()), TokenKind::Colon])
+                {
+                    let name: String = self.expect_identifier()?;
+                    self.advance();
+                    let value: Expr = self.parse_expression()?;
+                    named_arguments.push((name, value));
+                } else if named_arguments.is_empty() {
+                    let value: Expr = self.parse_expression()?;
+                    arguments.push(value);
+                } else {
+                    return Err(self.error(ParseErrorType::PositionalAfterNamedArgument));
+                }
 
                 match self.peek()?.kind {
                     TokenKind::Comma => {
@@ -1124,11 +1662,7 @@ impl Parser {
                         break;
                     }
                     _ => {
-                        let peek: &Token = self.peek()?;
-                        return Err(format!(
-                            "Expected ',' or ')', found '{:?}' at {}:{}",
-                            peek.kind, peek.start.0, peek.start.1
-                        ));
+                        return Err(self.unexpected(&[",", ")"]));
                     }
                 }
             }
@@ -1137,8 +1671,484 @@ impl Parser {
         let end: (usize, usize) = self.expect_token(&TokenKind::RightParen)?.end;
 
         Ok(Spanned {
-            node: Expression::Call { callee, arguments },
+            node: Expression::Call {
+                callee,
+                arguments,
+                named_arguments,
+            },
             span: Span { start, end },
         })
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod parser_tests {
+    use super::*;
+    use crate::types::{MutVisitor, Visitor, walk_expression, walk_expression_mut};
+    use lexer::Lexer;
+
+    fn parse(source: &str) -> Program {
+        Parser::parse(Lexer::tokenize(source).unwrap()).unwrap()
+    }
+
+    fn assert_round_trip(source: &str) {
+        let rendered: String = parse(source).to_source();
+        assert_eq!(parse(&rendered).to_source(), rendered);
+    }
+
+    #[test]
+    fn to_source_canonical() {
+        let source: &str = "@inline int add<T>(int a,int b){return a+b;}\n\
+            class Point{int x;static int count=0;\n\
+            static Self Point(int x){self.x=x;Point.count++;}\n\
+            int scaled(){(1+2)*self.x}}";
+        let expected: &str = "\
+@inline
+int add<T>(int a, int b) {
+    return a + b;
+}
+
+class Point {
+    int x;
+    static int count = 0;
+
+    static Self Point(int x) {
+        self.x = x;
+        Point.count = Point.count + 1;
+    }
+
+    int scaled() {
+        return (1 + 2) * self.x;
+    }
+}
+";
+        assert_eq!(parse(source).to_source(), expected);
+    }
+
+    #[test]
+    fn to_source_parenthesizes_by_precedence() {
+        let source: &str = "void f() { x = a - (b - c) + (d - e); y = (a < b) == c; \
+            z = !(a || b) && (1).toString() == \"v${a}\"; }";
+        let program: Program = parse(source);
+        let expected: &str = "\
+void f() {
+    x = a - (b - c) + (d - e);
+    y = (a < b) == c;
+    z = !(a || b) && (1).toString() == \"v\" + a.toString();
+}
+";
+        assert_eq!(program.to_source(), expected);
+    }
+
+    #[test]
+    fn modulo_and_power_precedence() {
+        let source: &str = "void f() { x = 2 ** (3 ** 2) % 5; y = (2 ** 3) ** 2; z = a * b % c; }";
+        let expected: &str = "\
+void f() {
+    x = 2 ** 3 ** 2 % 5;
+    y = (2 ** 3) ** 2;
+    z = a * b % c;
+}
+";
+        assert_eq!(parse(source).to_source(), expected);
+    }
+
+    #[test]
+    fn negation() {
+        let program: Program = parse("void f() { x = -a - -(b + 1) * - -c; }");
+        assert_eq!(
+            program.to_source(),
+            "void f() {\n    x = -a - -(b + 1) * -(-c);\n}\n"
+        );
+    }
+
+    #[test]
+    fn chained_comparisons() {
+        for source in ["a < b < c", "a < b == c", "a < b + c < d"] {
+            let tokens: Vec<Token> =
+                Lexer::tokenize(&format!("void f() {{ x = {source}; }}")).unwrap();
+            let error: ParseError = Parser::parse(tokens).unwrap_err();
+            assert_eq!(
+                error.error_type,
+                ParseErrorType::ChainedComparison,
+                "{source}"
+            );
+        }
+
+        parse("void f() { x = a < b && c < d; y = (a < b) == c; }");
+    }
+
+    #[test]
+    fn named_arguments() {
+        struct CallArguments(Vec<(usize, Vec<String>)>);
+
+        impl Visitor for CallArguments {
+            fn visit_expression(&mut self, expression: &Expr) {
+                if let Expression::Call {
+                    arguments,
+                    named_arguments,
+                    ..
+                } = &expression.node
+                {
+                    let names: Vec<String> =
+                        named_arguments.iter().map(|(n, _)| n.clone()).collect();
+                    self.0.push((arguments.len(), names));
+                }
+                walk_expression(self, expression);
+            }
+        }
+
+        let program: Program = parse("void f() { area(1, height: 4, width: 3); }");
+        let mut calls: CallArguments = CallArguments(Vec::new());
+        calls.visit_program(&program);
+        assert_eq!(
+            calls.0,
+            [(1, vec![String::from("height"), String::from("width")])]
+        );
+        assert_eq!(
+            program.to_source(),
+            "void f() {\n    area(1, height: 4, width: 3);\n}\n"
+        );
+
+        let tokens: Vec<Token> = Lexer::tokenize("void f() { area(width: 3, 4); }").unwrap();
+        let error: ParseError = Parser::parse(tokens).unwrap_err();
+        assert_eq!(
+            error.error_type,
+            ParseErrorType::PositionalAfterNamedArgument
+        );
+    }
+
+    #[test]
+    fn visitors() {
+        struct IdentifierCounter(usize);
+
+        impl Visitor for IdentifierCounter {
+            fn visit_expression(&mut self, expression: &Expr) {
+                if matches!(expression.node, Expression::Identifier(_)) {
+                    self.0 += 1;
+                }
+                walk_expression(self, expression);
+            }
+        }
+
+        struct Renamer;
+
+        impl MutVisitor for Renamer {
+            fn visit_expression_mut(&mut self, expression: &mut Expr) {
+                if let Expression::Identifier(name) = &mut expression.node {
+                    name.make_ascii_uppercase();
+                }
+                walk_expression_mut(self, expression);
+            }
+        }
+
+        let mut program: Program =
+            parse("void f(int a) { int b = a; while (b < a) { b = g(|int c| c + a, [b]); } }");
+
+        let mut counter: IdentifierCounter = IdentifierCounter(0);
+        counter.visit_program(&program);
+        assert_eq!(counter.0, 8);
+
+        Renamer.visit_program_mut(&mut program);
+        assert_eq!(
+            program.to_source(),
+            "void f(int a) {\n    int b = A;\n    while (B < A) {\n        B = G(|int c| C + A, [B]);\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn break_and_continue() {
+        let program: Program = parse("void f() { while (true) { break; continue; } }");
+        let names: Vec<&str> = match &program.statements[0].node {
+            Statement::FunctionDeclaration { body, .. } => match &body[0].node {
+                Statement::While { body, .. } => body.iter().map(|s| s.node.name()).collect(),
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+
+        assert_eq!(names, vec!["Break", "Continue"]);
+        assert_round_trip("void f() { do { break; } while (true); }");
+        assert!(Parser::parse(Lexer::tokenize("break;").unwrap()).is_err());
+    }
+
+    #[test]
+    fn throw_statement() {
+        let program: Program = parse("void f() { try { throw \"failed\"; } catch (e) {} }");
+        let names: Vec<&str> = match &program.statements[0].node {
+            Statement::FunctionDeclaration { body, .. } => match &body[0].node {
+                Statement::Try { body, .. } => body.iter().map(|s| s.node.name()).collect(),
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+
+        assert_eq!(names, vec!["Throw"]);
+        assert_round_trip("void f() { throw \"a\" + \"b\"; }");
+        assert!(Parser::parse(Lexer::tokenize("throw \"failed\";").unwrap()).is_err());
+    }
+
+    #[test]
+    fn class_superclass() {
+        let program: Program = parse("class Animal {} class Dog : Animal { int age; }");
+        let superclasses: Vec<Option<String>> = program
+            .statements
+            .iter()
+            .map(|statement| match &statement.node {
+                Statement::ClassDeclaration { superclass, .. } => superclass.clone(),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(superclasses, vec![None, Some(String::from("Animal"))]);
+        assert_round_trip("class Dog<T> : Animal { int age; }");
+    }
+
+    #[test]
+    fn to_source_round_trip() {
+        assert_round_trip(include_str!("../../test.cl"));
+        assert_round_trip(include_str!("../../test2.cl"));
+        assert_round_trip(
+            "import \"lib/math\";\n\
+            enum Shape { Circle(float), Rect(float, float), Empty }\n\
+            interface Area { float area(); void scale(float by); }\n\
+            class Main { const int MAX = 3;\n\
+            static int main() {\n\
+                let (a, b) = (1, 2.0);\n\
+                let d = a + 1;\n\
+                let c: string? = null;\n\
+                int[] xs = [1, 2, 3];\n\
+                do { a++; } while (a < MAX);\n\
+                try { xs[0] = a; } catch (e) {}\n\
+                if (a > 1) { return 1; } else if (!true) {} else { Builtin.println(\"\\tq\\\"\\${x}\"); }\n\
+                f = |int x| x * 2; g = || { return {\"k\": 1}; };\n\
+                return 0;\n\
+            } }",
+        );
+    }
+
+    /// Parses the given statements as the body of a function and returns the values they assign
+    /// or declare.
+    fn values(body: &str) -> Vec<Expression> {
+        let program: Program = parse(&format!("void f() {{ {body} }}"));
+        let Statement::FunctionDeclaration { body, .. } = &program.statements[0].node else {
+            unreachable!()
+        };
+        body.iter()
+            .map(|statement| match &statement.node {
+                Statement::Assignment { value, .. }
+                | Statement::DestructuringDeclaration { value, .. }
+                | Statement::VariableDeclaration {
+                    value: Some(value), ..
+                } => value.node.clone(),
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lambdas() {
+        let values: Vec<Expression> = values("g = |int x, float y| x * y; h = || { return 1; };");
+
+        let Expression::Lambda { parameters, body } = &values[0] else {
+            unreachable!()
+        };
+        // Parameters span from their type to their name
+        assert_eq!(
+            parameters,
+            &[
+                Parameter {
+                    type_: String::from("int"),
+                    name: String::from("x"),
+                    span: Span {
+                        start: (1, 17),
+                        end: (1, 21)
+                    }
+                },
+                Parameter {
+                    type_: String::from("float"),
+                    name: String::from("y"),
+                    span: Span {
+                        start: (1, 24),
+                        end: (1, 30)
+                    }
+                }
+            ]
+        );
+        // An expression body is stored as a return statement
+        assert!(matches!(
+            &body[..],
+            [Spanned {
+                node: Statement::Return(Some(_)),
+                ..
+            }]
+        ));
+
+        let Expression::Lambda { parameters, body } = &values[1] else {
+            unreachable!()
+        };
+        assert!(parameters.is_empty());
+        assert_eq!(body.len(), 1);
+
+        // Parameters need a type
+        let tokens: Vec<Token> = Lexer::tokenize("void f() { g = |x| x; }").unwrap();
+        let error: ParseError = Parser::parse(tokens).unwrap_err();
+        assert_eq!(error.span.start, (1, 18));
+    }
+
+    #[test]
+    fn array_literals_and_indexing() {
+        let values: Vec<Expression> = values("xs = [1, 2, 3,]; ys = []; y = xs[0][i + 1];");
+
+        assert!(matches!(&values[0], Expression::ArrayLiteral(elements) if elements.len() == 3));
+        assert!(matches!(&values[1], Expression::ArrayLiteral(elements) if elements.is_empty()));
+
+        let Expression::Index { object, index } = &values[2] else {
+            unreachable!()
+        };
+        assert!(matches!(object.node, Expression::Index { .. }));
+        assert!(matches!(index.node, Expression::Binary { .. }));
+    }
+
+    #[test]
+    fn map_literals() {
+        let values: Vec<Expression> = values("m = {\"a\": 1, \"b\": x + 1}; e = {};");
+
+        let Expression::MapLiteral(entries) = &values[0] else {
+            unreachable!()
+        };
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0].0.node,
+            Expression::Literal(Literal::String(String::from("a")))
+        );
+        assert!(matches!(entries[1].1.node, Expression::Binary { .. }));
+
+        assert!(matches!(&values[1], Expression::MapLiteral(entries) if entries.is_empty()));
+    }
+
+    #[test]
+    fn tuples() {
+        let values: Vec<Expression> = values("t = (1, 2.0, x); let (a, b) = (1, 2); p = (1);");
+
+        assert!(matches!(&values[0], Expression::Tuple(elements) if elements.len() == 3));
+        assert!(matches!(&values[1], Expression::Tuple(elements) if elements.len() == 2));
+        // Parentheses around a single expression only group it
+        assert_eq!(values[2], Expression::Literal(Literal::Integer(1)));
+
+        let program: Program = parse("void f() { let (a, b) = t; }");
+        let Statement::FunctionDeclaration { body, .. } = &program.statements[0].node else {
+            unreachable!()
+        };
+        assert!(matches!(
+            &body[0].node,
+            Statement::DestructuringDeclaration { names, .. } if names == &["a", "b"]
+        ));
+    }
+
+    #[test]
+    fn unexpected_token_messages() {
+        let message = |source: &str| {
+            let tokens: Vec<Token> = Lexer::tokenize(source).unwrap();
+            Parser::parse(tokens).unwrap_err().error_type.message()
+        };
+
+        assert_eq!(
+            message("int x +"),
+            "Expected '=', ';', '(' or '<', found '+'"
+        );
+        assert_eq!(
+            message("void f(int a b) {}"),
+            "Expected ',' or ')', found 'b'"
+        );
+        assert_eq!(
+            message("void f() { x.; }"),
+            "Expected 'identifier', found ';'"
+        );
+        assert_eq!(
+            message("void f() { return 1"),
+            "Expected ';', found end of input"
+        );
+    }
+
+    #[test]
+    fn implicit_return() {
+        assert_eq!(
+            parse("int f(int x) { x = x * 2; x + 1 } void g() { f(1) }").to_source(),
+            "int f(int x) {\n    x = x * 2;\n    return x + 1;\n}\n\n\
+             void g() {\n    f(1);\n}\n"
+        );
+
+        // Errors before the closing brace belong to the statement and are not retried as a
+        // trailing expression
+        let error = |source: &str| {
+            let tokens: Vec<Token> = Lexer::tokenize(source).unwrap();
+            Parser::parse(tokens).unwrap_err()
+        };
+
+        let missing_semicolon: ParseError = error("int f(int x) { x + 1 x }");
+        assert_eq!(
+            missing_semicolon.error_type.message(),
+            "Expected ';', found 'x'"
+        );
+        assert_eq!(missing_semicolon.span.start, (1, 22));
+
+        let invalid_trailing: ParseError = error("int f(int x) { x = }");
+        assert_eq!(
+            invalid_trailing.error_type.message(),
+            "Expected 'expression', found '}'"
+        );
+        assert_eq!(invalid_trailing.span.start, (1, 20));
+
+        // Statement blocks have no value, so a trailing expression still needs its semicolon
+        let in_block: ParseError = error("int f(bool b) { if (b) { 1 } return 2; }");
+        assert_eq!(in_block.error_type.message(), "Expected ';', found '}'");
+        assert_eq!(in_block.span.start, (1, 28));
+    }
+
+    #[test]
+    fn statement_spans() {
+        let program: Program = parse("int f(int x) {\n    x = x + 1;\n    return x;\n}");
+        let Statement::FunctionDeclaration { body, .. } = &program.statements[0].node else {
+            unreachable!()
+        };
+
+        // Statements span from their first token through the semicolon
+        let Statement::Assignment { value, .. } = &body[0].node else {
+            unreachable!()
+        };
+        assert_eq!(
+            body[0].span,
+            Span {
+                start: (2, 5),
+                end: (2, 14)
+            }
+        );
+        assert_eq!(
+            value.span,
+            Span {
+                start: (2, 9),
+                end: (2, 13)
+            }
+        );
+
+        let Statement::Return(Some(value)) = &body[1].node else {
+            unreachable!()
+        };
+        assert_eq!(
+            body[1].span,
+            Span {
+                start: (3, 5),
+                end: (3, 13)
+            }
+        );
+        assert_eq!(
+            value.span,
+            Span {
+                start: (3, 12),
+                end: (3, 12)
+            }
+        );
+    }
+}
@@ -1,7 +1,12 @@
 //! Contains types used throughout the parser module.
 
+use std::fmt::{self, Display, Formatter, Write};
+
+use lexer::types::TokenKind;
+
 /// Contains literal values in the AST.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Literal {
     /// An integer literal.
     Integer(i64),
@@ -11,10 +16,57 @@ pub enum Literal {
     String(String),
     /// A boolean literal.
     Boolean(bool),
+    /// The null literal.
+    Null,
+}
+
+/// An attribute attached to a declaration, e.g. `@deprecated("Use bar instead")`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Attribute {
+    /// The name of the attribute.
+    pub name: String,
+    /// The literal arguments of the attribute.
+    pub arguments: Vec<Literal>,
+}
+
+/// Represents a variant of an enum declaration, e.g. `Red` or `Rgb(int, int, int)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EnumVariant {
+    /// The name of the variant.
+    pub name: String,
+    /// The types of the values carried by the variant, empty if it carries none.
+    pub payload: Vec<String>,
+}
+
+/// Represents a parameter of a function, method or lambda, e.g. `int x`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Parameter {
+    /// The type of the parameter.
+    pub type_: String,
+    /// The name of the parameter.
+    pub name: String,
+    /// The span of the parameter, from its type to its name.
+    pub span: Span,
+}
+
+/// Represents a method signature declared by an interface, e.g. `float area();`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MethodSignature {
+    /// The return type of the method.
+    pub return_type: String,
+    /// The name of the method.
+    pub name: String,
+    /// The parameters of the method.
+    pub parameters: Vec<Parameter>,
 }
 
 /// Represents binary operators.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BinaryOperator {
     /// Represents binary addition.
     Add,
@@ -24,6 +76,10 @@ pub enum BinaryOperator {
     Multiply,
     /// Represents binary division.
     Divide,
+    /// Represents the remainder of a division.
+    Modulo,
+    /// Represents exponentiation.
+    Power,
     /// Represents equality comparison.
     Equals,
     /// Represents inequality comparison.
@@ -42,15 +98,229 @@ pub enum BinaryOperator {
     Or,
 }
 
+/// Describes how a chain of binary operators with the same precedence is grouped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    /// `a - b - c` is grouped as `(a - b) - c`.
+    Left,
+    /// `a ^ b ^ c` is grouped as `a ^ (b ^ c)`.
+    Right,
+    /// The operator cannot be chained, e.g. `a < b < c` is an error.
+    NonAssociative,
+}
+
+/// An entry of the [`BINARY_OPERATORS`] table.
+#[derive(Debug)]
+pub struct OperatorInfo {
+    /// The token the operator is written as.
+    pub token: TokenKind,
+    /// The operator in the AST.
+    pub operator: BinaryOperator,
+    /// The operator as written in source code.
+    pub symbol: &'static str,
+    /// The binding strength of the operator, higher binds tighter.
+    pub precedence: u8,
+    /// How chains of operators with this precedence are grouped.
+    pub associativity: Associativity,
+    /// The name of the operator in the methods implementing it, e.g. `Add` for `_bopAdd`.
+    pub method: &'static str,
+}
+
+/// The table of all binary operators.
+///
+/// Used for parsing, for rendering source code and for naming the methods that implement the
+/// operators on each type, so adding an operator only needs a new [`BinaryOperator`] variant, its
+/// token, an entry here and the methods in the builtin types.
+pub static BINARY_OPERATORS: [OperatorInfo; 14] = [
+    binary_operator(
+        TokenKind::AsteriskAsterisk,
+        BinaryOperator::Power,
+        "**",
+        6,
+        Associativity::Right,
+        "Pow",
+    ),
+    binary_operator(
+        TokenKind::Asterisk,
+        BinaryOperator::Multiply,
+        "*",
+        5,
+        Associativity::Left,
+        "Mul",
+    ),
+    binary_operator(
+        TokenKind::Slash,
+        BinaryOperator::Divide,
+        "/",
+        5,
+        Associativity::Left,
+        "Div",
+    ),
+    binary_operator(
+        TokenKind::Percent,
+        BinaryOperator::Modulo,
+        "%",
+        5,
+        Associativity::Left,
+        "Mod",
+    ),
+    binary_operator(
+        TokenKind::Plus,
+        BinaryOperator::Add,
+        "+",
+        4,
+        Associativity::Left,
+        "Add",
+    ),
+    binary_operator(
+        TokenKind::Minus,
+        BinaryOperator::Subtract,
+        "-",
+        4,
+        Associativity::Left,
+        "Sub",
+    ),
+    binary_operator(
+        TokenKind::EqualsEquals,
+        BinaryOperator::Equals,
+        "==",
+        3,
+        Associativity::NonAssociative,
+        "Eq",
+    ),
+    binary_operator(
+        TokenKind::NotEquals,
+        BinaryOperator::NotEquals,
+        "!=",
+        3,
+        Associativity::NonAssociative,
+        "Ne",
+    ),
+    binary_operator(
+        TokenKind::LeftAngle,
+        BinaryOperator::LessThan,
+        "<",
+        3,
+        Associativity::NonAssociative,
+        "Lt",
+    ),
+    binary_operator(
+        TokenKind::RightAngle,
+        BinaryOperator::GreaterThan,
+        ">",
+        3,
+        Associativity::NonAssociative,
+        "Gt",
+    ),
+    binary_operator(
+        TokenKind::LessThanOrEqual,
+        BinaryOperator::LessThanOrEqual,
+        "<=",
+        3,
+        Associativity::NonAssociative,
+        "Le",
+    ),
+    binary_operator(
+        TokenKind::GreaterThanOrEqual,
+        BinaryOperator::GreaterThanOrEqual,
+        ">=",
+        3,
+        Associativity::NonAssociative,
+        "Ge",
+    ),
+    binary_operator(
+        TokenKind::And,
+        BinaryOperator::And,
+        "&&",
+        2,
+        Associativity::Left,
+        "And",
+    ),
+    binary_operator(
+        TokenKind::Or,
+        BinaryOperator::Or,
+        "||",
+        1,
+        Associativity::Left,
+        "Or",
+    ),
+];
+
+const fn binary_operator(
+    token: TokenKind,
+    operator: BinaryOperator,
+    symbol: &'static str,
+    precedence: u8,
+    associativity: Associativity,
+    method: &'static str,
+) -> OperatorInfo {
+    OperatorInfo {
+        token,
+        operator,
+        symbol,
+        precedence,
+        associativity,
+        method,
+    }
+}
+
+impl BinaryOperator {
+    /// Returns the [`BINARY_OPERATORS`] entry of the operator written as the given token, if any.
+    #[must_use]
+    pub fn from_token(token: &TokenKind) -> Option<&'static OperatorInfo> {
+        BINARY_OPERATORS.iter().find(|info| &info.token == token)
+    }
+
+    /// Returns the [`BINARY_OPERATORS`] entry of the operator.
+    ///
+    /// # Panics
+    /// If the operator is missing from the table.
+    #[must_use]
+    pub fn info(&self) -> &'static OperatorInfo {
+        BINARY_OPERATORS
+            .iter()
+            .find(|info| &info.operator == self)
+            .expect("Every binary operator has an entry in the operator table")
+    }
+
+    /// Returns the name of the method on the left operand's type implementing the operator.
+    #[must_use]
+    pub fn method_name(&self) -> String {
+        format!("_bop{}", self.info().method)
+    }
+
+    /// Returns the name of the method on the right operand's type implementing the operator,
+    /// which takes precedence over [`Self::method_name`].
+    #[must_use]
+    pub fn reflected_method_name(&self) -> String {
+        format!("_bopR{}", self.info().method)
+    }
+}
+
 /// Represents unary operators.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UnaryOperator {
     /// Represents logical NOT operation.
     Not,
+    /// Represents arithmetic negation.
+    Negate,
+}
+
+impl UnaryOperator {
+    /// Returns the name of the method on the operand's type implementing the operator.
+    #[must_use]
+    pub fn method_name(&self) -> String {
+        String::from(match self {
+            Self::Not => "_uopNot",
+            Self::Negate => "_uopNeg",
+        })
+    }
 }
 
 /// Represents expressions in the AST.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Expression {
     /// A literal expression.
     Literal(Literal),
@@ -76,8 +346,23 @@ pub enum Expression {
     Call {
         /// The callee being called.
         callee: Box<Expr>,
-        /// The arguments passed to the function.
+        /// The positional arguments passed to the function.
         arguments: Vec<Expr>,
+        /// The named arguments following the positional ones, e.g. `width: 3`.
+        named_arguments: Vec<(String, Expr)>,
+    },
+    /// An array literal expression, e.g. `[1, 2, 3]`.
+    ArrayLiteral(Vec<Expr>),
+    /// A tuple expression, e.g. `(a, b, c)`.
+    Tuple(Vec<Expr>),
+    /// A map literal expression, e.g. `{ "key": value }`.
+    MapLiteral(Vec<(Expr, Expr)>),
+    /// An index expression, e.g. `array[0]` or `map["key"]`.
+    Index {
+        /// The expression being indexed.
+        object: Box<Expr>,
+        /// The index expression.
+        index: Box<Expr>,
     },
     /// A member access expression.
     MemberAccess {
@@ -88,6 +373,13 @@ pub enum Expression {
     },
     /// Special expression representing the current class instance.
     Self_,
+    /// An anonymous function expression, e.g. `|int x| x * 2`.
+    Lambda {
+        /// The parameters of the lambda.
+        parameters: Vec<Parameter>,
+        /// The body of the lambda. An expression body is stored as a single return statement.
+        body: Vec<Stmt>,
+    },
 }
 
 impl Expression {
@@ -100,24 +392,48 @@ impl Expression {
             Self::Binary { .. } => "Binary",
             Self::Unary { .. } => "Unary",
             Self::Call { .. } => "Call",
+            Self::ArrayLiteral(_) => "ArrayLiteral",
+            Self::Tuple(_) => "Tuple",
+            Self::MapLiteral(_) => "MapLiteral",
+            Self::Index { .. } => "Index",
             Self::MemberAccess { .. } => "MemberAccess",
             Self::Self_ => "Self",
+            Self::Lambda { .. } => "Lambda",
         }
     }
 }
 
 /// Represents statements in the AST.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Statement {
     /// A variable declaration statement.
     VariableDeclaration {
-        /// The type of the variable.
+        /// The type of the variable, or an empty string if it is inferred from the value, e.g. in
+        /// `let x = 5;`.
         type_: String,
         /// The name of the variable.
         name: String,
         /// The initial value of the variable.
         value: Option<Expr>,
     },
+    /// A destructuring declaration statement, e.g. `let (x, y) = expr;`.
+    DestructuringDeclaration {
+        /// The names of the declared variables, in order.
+        names: Vec<String>,
+        /// The value being destructured.
+        value: Expr,
+    },
+    /// A constant declaration statement, e.g. `const int MAX = 10;`. Declares a local constant
+    /// inside functions and methods, or a static constant field inside classes.
+    ConstDeclaration {
+        /// The type of the constant.
+        type_: String,
+        /// The name of the constant.
+        name: String,
+        /// The value of the constant.
+        value: Expr,
+    },
     /// A field declaration statement.
     FieldDeclaration {
         /// The type of the field.
@@ -142,10 +458,14 @@ pub enum Statement {
         return_type: String,
         /// The name of the function.
         name: String,
-        /// The parameters of the function `(Type, Identifier)`.
-        parameters: Vec<(String, String)>,
+        /// The parameters of the function.
+        parameters: Vec<Parameter>,
         /// The body of the function.
         body: Vec<Stmt>,
+        /// The attributes attached to the function.
+        attributes: Vec<Attribute>,
+        /// The names of the generic type parameters of the function, e.g. `T` in `T id<T>(T x)`.
+        generics: Vec<String>,
     },
     /// A class declaration statement.
     ClassDeclaration {
@@ -153,6 +473,26 @@ pub enum Statement {
         name: String,
         /// The body of the class.
         body: Vec<Stmt>,
+        /// The attributes attached to the class.
+        attributes: Vec<Attribute>,
+        /// The names of the generic type parameters of the class, e.g. `T` in `class Box<T>`.
+        generics: Vec<String>,
+        /// The class this class inherits from, e.g. `Animal` in `class Dog : Animal`.
+        superclass: Option<String>,
+    },
+    /// An enum declaration statement.
+    EnumDeclaration {
+        /// The name of the enum.
+        name: String,
+        /// The variants of the enum.
+        variants: Vec<EnumVariant>,
+    },
+    /// An interface declaration statement.
+    InterfaceDeclaration {
+        /// The name of the interface.
+        name: String,
+        /// The method signatures required by the interface.
+        methods: Vec<MethodSignature>,
     },
     /// A method declaration statement.
     MethodDeclaration {
@@ -160,12 +500,16 @@ pub enum Statement {
         return_type: String,
         /// The name of the function.
         name: String,
-        /// The parameters of the function `(Type, Identifier)`.
-        parameters: Vec<(String, String)>,
+        /// The parameters of the function.
+        parameters: Vec<Parameter>,
         /// The body of the function.
         body: Vec<Stmt>,
         /// Static method or not.
         static_: bool,
+        /// The attributes attached to the method.
+        attributes: Vec<Attribute>,
+        /// The names of the generic type parameters of the method.
+        generics: Vec<String>,
     },
     /// If statement.
     If {
@@ -181,8 +525,32 @@ pub enum Statement {
         /// The body of the while loop.
         body: Vec<Stmt>,
     },
+    /// Do-while loop statement, which checks its condition after each iteration.
+    DoWhile {
+        /// The body of the loop.
+        body: Vec<Stmt>,
+        /// The condition expression.
+        condition: Expr,
+    },
+    /// Try/catch statement.
+    Try {
+        /// The guarded block.
+        body: Vec<Stmt>,
+        /// The name the caught error is bound to inside the handler.
+        error_name: String,
+        /// The block to execute if the guarded block raises an error.
+        handler: Vec<Stmt>,
+    },
     /// A return statement.
     Return(Option<Expr>),
+    /// A break statement, leaving the innermost loop.
+    Break,
+    /// A continue statement, skipping to the next iteration of the innermost loop.
+    Continue,
+    /// A throw statement, raising an error with the given message.
+    Throw(Expr),
+    /// An import statement, e.g. `import "path/module";`.
+    Import(String),
     /// An expression statement.
     Expression(Expr),
 }
@@ -193,14 +561,24 @@ impl Statement {
     pub const fn name(&self) -> &str {
         match self {
             Self::VariableDeclaration { .. } => "VariableDeclaration",
+            Self::DestructuringDeclaration { .. } => "DestructuringDeclaration",
+            Self::ConstDeclaration { .. } => "ConstDeclaration",
             Self::FieldDeclaration { .. } => "FieldDeclaration",
             Self::Assignment { .. } => "Assignment",
             Self::FunctionDeclaration { .. } => "FunctionDeclaration",
             Self::ClassDeclaration { .. } => "ClassDeclaration",
+            Self::EnumDeclaration { .. } => "EnumDeclaration",
+            Self::InterfaceDeclaration { .. } => "InterfaceDeclaration",
             Self::MethodDeclaration { .. } => "MethodDeclaration",
             Self::If { .. } => "If",
             Self::While { .. } => "While",
+            Self::DoWhile { .. } => "DoWhile",
+            Self::Try { .. } => "Try",
             Self::Return(_) => "Return",
+            Self::Break => "Break",
+            Self::Continue => "Continue",
+            Self::Throw(_) => "Throw",
+            Self::Import(_) => "Import",
             Self::Expression(_) => "Expression",
         }
     }
@@ -208,6 +586,7 @@ impl Statement {
 
 /// The root node of the AST.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Program {
     /// A list of statements in the program.
     pub statements: Vec<Stmt>,
@@ -215,6 +594,7 @@ pub struct Program {
 
 /// Represents the starting and ending position of a node in the source code.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Span {
     /// The starting position (line, column).
     pub start: (usize, usize),
@@ -224,6 +604,7 @@ pub struct Span {
 
 /// A node with its associated span in the source code.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Spanned<T> {
     /// The value with its span.
     pub node: T,
@@ -235,3 +616,824 @@ pub struct Spanned<T> {
 pub type Stmt = Spanned<Statement>;
 /// Spanned expression
 pub type Expr = Spanned<Expression>;
+
+impl Program {
+    /// Renders the program back to canonical source code. Parsing the result again yields the
+    /// same AST, apart from spans.
+    #[must_use]
+    pub fn to_source(&self) -> String {
+        let mut out: String = String::new();
+        for (i, statement) in self.statements.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            write_statement(&mut out, &statement.node, 0);
+        }
+        out
+    }
+}
+
+impl Display for Program {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_source())
+    }
+}
+
+impl Display for Statement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut out: String = String::new();
+        write_statement(&mut out, self, 0);
+        f.write_str(out.trim_end_matches('\n'))
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut out: String = String::new();
+        write_expression(&mut out, self, 0);
+        f.write_str(&out)
+    }
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::Float(value) => {
+                let value: String = value.to_string();
+                if value.contains('.') {
+                    f.write_str(&value)
+                } else {
+                    write!(f, "{value}.0")
+                }
+            }
+            Self::String(value) => {
+                f.write_char('"')?;
+                let mut chars = value.chars().peekable();
+                while let Some(ch) = chars.next() {
+                    match ch {
+                        '"' => f.write_str("\\\"")?,
+                        '\\' => f.write_str("\\\\")?,
+                        '\n' => f.write_str("\\n")?,
+                        '\t' => f.write_str("\\t")?,
+                        '\r' => f.write_str("\\r")?,
+                        '\0' => f.write_str("\\0")?,
+                        '\x07' => f.write_str("\\a")?,
+                        '\x08' => f.write_str("\\b")?,
+                        '\x0B' => f.write_str("\\v")?,
+                        '\x0C' => f.write_str("\\f")?,
+                        '$' if chars.peek() == Some(&'{') => f.write_str("\\$")?,
+                        other => f.write_char(other)?,
+                    }
+                }
+                f.write_char('"')
+            }
+            Self::Boolean(value) => write!(f, "{value}"),
+            Self::Null => f.write_str("null"),
+        }
+    }
+}
+
+impl Display for BinaryOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.info().symbol)
+    }
+}
+
+impl Display for UnaryOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Not => "!",
+            Self::Negate => "-",
+        })
+    }
+}
+
+const INDENT: &str = "    ";
+
+fn write_indent(out: &mut String, indent: usize) {
+    out.push_str(&INDENT.repeat(indent));
+}
+
+fn write_block(out: &mut String, statements: &[Stmt], indent: usize) {
+    if statements.is_empty() {
+        out.push_str("{}");
+        return;
+    }
+
+    out.push_str("{\n");
+    for statement in statements {
+        write_statement(out, &statement.node, indent + 1);
+    }
+    write_indent(out, indent);
+    out.push('}');
+}
+
+fn write_class_body(out: &mut String, members: &[Stmt], indent: usize) {
+    if members.is_empty() {
+        out.push_str("{}");
+        return;
+    }
+
+    out.push_str("{\n");
+    for (i, member) in members.iter().enumerate() {
+        let is_method = |member: &Stmt| matches!(member.node, Statement::MethodDeclaration { .. });
+        if i > 0 && (is_method(member) || is_method(&members[i - 1])) {
+            out.push('\n');
+        }
+        write_statement(out, &member.node, indent + 1);
+    }
+    write_indent(out, indent);
+    out.push('}');
+}
+
+fn write_attributes(out: &mut String, attributes: &[Attribute], indent: usize) {
+    for attribute in attributes {
+        out.push('@');
+        out.push_str(&attribute.name);
+        if !attribute.arguments.is_empty() {
+            let arguments: Vec<String> = attribute
+                .arguments
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            out.push('(');
+            out.push_str(&arguments.join(", "));
+            out.push(')');
+        }
+        out.push('\n');
+        write_indent(out, indent);
+    }
+}
+
+fn write_generics(out: &mut String, generics: &[String]) {
+    if !generics.is_empty() {
+        out.push('<');
+        out.push_str(&generics.join(", "));
+        out.push('>');
+    }
+}
+
+fn write_parameters(out: &mut String, parameters: &[Parameter]) {
+    let parameters: Vec<String> = parameters
+        .iter()
+        .map(|parameter| format!("{} {}", parameter.type_, parameter.name))
+        .collect();
+    out.push_str(&parameters.join(", "));
+}
+
+fn write_declaration(
+    out: &mut String,
+    type_: &str,
+    name: &str,
+    value: Option<&Expr>,
+    indent: usize,
+) {
+    if type_.is_empty() {
+        out.push_str("let ");
+    } else {
+        out.push_str(type_);
+        out.push(' ');
+    }
+    out.push_str(name);
+    if let Some(value) = value {
+        out.push_str(" = ");
+        write_expression(out, &value.node, indent);
+    }
+    out.push(';');
+}
+
+#[allow(clippy::too_many_lines)]
+fn write_statement(out: &mut String, statement: &Statement, indent: usize) {
+    write_indent(out, indent);
+    match statement {
+        Statement::VariableDeclaration { type_, name, value } => {
+            write_declaration(out, type_, name, value.as_ref(), indent);
+        }
+        Statement::DestructuringDeclaration { names, value } => {
+            out.push_str("let (");
+            out.push_str(&names.join(", "));
+            out.push_str(") = ");
+            write_expression(out, &value.node, indent);
+            out.push(';');
+        }
+        Statement::ConstDeclaration { type_, name, value } => {
+            out.push_str("const ");
+            write_declaration(out, type_, name, Some(value), indent);
+        }
+        Statement::FieldDeclaration {
+            type_,
+            name,
+            value,
+            static_,
+        } => {
+            if *static_ {
+                out.push_str("static ");
+            }
+            write_declaration(out, type_, name, value.as_ref(), indent);
+        }
+        Statement::Assignment { assignee, value } => {
+            write_expression(out, &assignee.node, indent);
+            out.push_str(" = ");
+            write_expression(out, &value.node, indent);
+            out.push(';');
+        }
+        Statement::FunctionDeclaration {
+            return_type,
+            name,
+            parameters,
+            body,
+            attributes,
+            generics,
+        } => {
+            write_attributes(out, attributes, indent);
+            out.push_str(return_type);
+            out.push(' ');
+            out.push_str(name);
+            write_generics(out, generics);
+            out.push('(');
+            write_parameters(out, parameters);
+            out.push_str(") ");
+            write_block(out, body, indent);
+        }
+        Statement::ClassDeclaration {
+            name,
+            body,
+            attributes,
+            generics,
+            superclass,
+        } => {
+            write_attributes(out, attributes, indent);
+            out.push_str("class ");
+            out.push_str(name);
+            write_generics(out, generics);
+            if let Some(superclass) = superclass {
+                out.push_str(" : ");
+                out.push_str(superclass);
+            }
+            out.push(' ');
+            write_class_body(out, body, indent);
+        }
+        Statement::EnumDeclaration { name, variants } => {
+            out.push_str("enum ");
+            out.push_str(name);
+            out.push_str(" {\n");
+            for variant in variants {
+                write_indent(out, indent + 1);
+                out.push_str(&variant.name);
+                if !variant.payload.is_empty() {
+                    out.push('(');
+                    out.push_str(&variant.payload.join(", "));
+                    out.push(')');
+                }
+                out.push_str(",\n");
+            }
+            write_indent(out, indent);
+            out.push('}');
+        }
+        Statement::InterfaceDeclaration { name, methods } => {
+            out.push_str("interface ");
+            out.push_str(name);
+            out.push_str(" {\n");
+            for method in methods {
+                write_indent(out, indent + 1);
+                out.push_str(&method.return_type);
+                out.push(' ');
+                out.push_str(&method.name);
+                out.push('(');
+                write_parameters(out, &method.parameters);
+                out.push_str(");\n");
+            }
+            write_indent(out, indent);
+            out.push('}');
+        }
+        Statement::MethodDeclaration {
+            return_type,
+            name,
+            parameters,
+            body,
+            static_,
+            attributes,
+            generics,
+        } => {
+            write_attributes(out, attributes, indent);
+            if return_type.is_empty() {
+                out.push_str("static Self");
+            } else {
+                if *static_ {
+                    out.push_str("static ");
+                }
+                out.push_str(return_type);
+            }
+            out.push(' ');
+            out.push_str(name);
+            write_generics(out, generics);
+            out.push('(');
+            write_parameters(out, parameters);
+            out.push_str(") ");
+            write_block(out, body, indent);
+        }
+        Statement::If {
+            conditional_branches,
+            else_branch,
+        } => {
+            for (i, (condition, body)) in conditional_branches.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(" else ");
+                }
+                out.push_str("if (");
+                write_expression(out, &condition.node, indent);
+                out.push_str(") ");
+                write_block(out, body, indent);
+            }
+            if let Some(body) = else_branch {
+                out.push_str(" else ");
+                write_block(out, body, indent);
+            }
+        }
+        Statement::While { condition, body } => {
+            out.push_str("while (");
+            write_expression(out, &condition.node, indent);
+            out.push_str(") ");
+            write_block(out, body, indent);
+        }
+        Statement::DoWhile { body, condition } => {
+            out.push_str("do ");
+            write_block(out, body, indent);
+            out.push_str(" while (");
+            write_expression(out, &condition.node, indent);
+            out.push_str(");");
+        }
+        Statement::Try {
+            body,
+            error_name,
+            handler,
+        } => {
+            out.push_str("try ");
+            write_block(out, body, indent);
+            out.push_str(" catch (");
+            out.push_str(error_name);
+            out.push_str(") ");
+            write_block(out, handler, indent);
+        }
+        Statement::Return(value) => {
+            out.push_str("return");
+            if let Some(value) = value {
+                out.push(' ');
+                write_expression(out, &value.node, indent);
+            }
+            out.push(';');
+        }
+        Statement::Break => out.push_str("break;"),
+        Statement::Continue => out.push_str("continue;"),
+        Statement::Throw(message) => {
+            out.push_str("throw ");
+            write_expression(out, &message.node, indent);
+            out.push(';');
+        }
+        Statement::Import(path) => {
+            out.push_str("import ");
+            out.push_str(&Literal::String(path.clone()).to_string());
+            out.push(';');
+        }
+        Statement::Expression(expr) => {
+            write_expression(out, &expr.node, indent);
+            out.push(';');
+        }
+    }
+    out.push('\n');
+}
+
+/// Writes the parenthesized arguments of a call, named arguments last.
+fn write_arguments(out: &mut String, arguments: &[Expr], named: &[(String, Expr)], indent: usize) {
+    out.push('(');
+    write_list(out, arguments, indent);
+    for (i, (name, value)) in named.iter().enumerate() {
+        if i > 0 || !arguments.is_empty() {
+            out.push_str(", ");
+        }
+        out.push_str(name);
+        out.push_str(": ");
+        write_expression(out, &value.node, indent);
+    }
+    out.push(')');
+}
+
+fn write_list(out: &mut String, expressions: &[Expr], indent: usize) {
+    for (i, expr) in expressions.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        write_expression(out, &expr.node, indent);
+    }
+}
+
+/// Writes an operand, wrapped in parentheses if `parenthesize` is set.
+fn write_operand(out: &mut String, expr: &Expression, indent: usize, parenthesize: bool) {
+    if parenthesize {
+        out.push('(');
+        write_expression(out, expr, indent);
+        out.push(')');
+    } else {
+        write_expression(out, expr, indent);
+    }
+}
+
+/// Writes the object of a call, member access or index expression. Anything that does not bind
+/// as tightly as a postfix operator is wrapped in parentheses, as are numbers so that the dot of
+/// a member access is not read as a decimal point.
+fn write_postfix_object(out: &mut String, expr: &Expression, indent: usize) {
+    let parenthesize: bool = matches!(
+        expr,
+        Expression::Binary { .. }
+            | Expression::Unary { .. }
+            | Expression::Lambda { .. }
+            | Expression::Literal(Literal::Integer(_) | Literal::Float(_))
+    );
+    write_operand(out, expr, indent, parenthesize);
+}
+
+#[allow(clippy::too_many_lines)]
+fn write_expression(out: &mut String, expr: &Expression, indent: usize) {
+    match expr {
+        Expression::Literal(literal) => out.push_str(&literal.to_string()),
+        Expression::Identifier(name) => out.push_str(name),
+        Expression::Binary {
+            left,
+            operator,
+            right,
+        } => {
+            let info: &OperatorInfo = operator.info();
+            // An operand needs parentheses if it binds weaker than the operator, or equally
+            // strong on the side the operator does not group towards.
+            let needs_parens = |operand: &Expr, grouping_side: Associativity| match &operand.node {
+                Expression::Binary {
+                    operator: inner, ..
+                } => {
+                    let inner: u8 = inner.info().precedence;
+                    inner < info.precedence
+                        || (inner == info.precedence && info.associativity != grouping_side)
+                }
+                Expression::Lambda { .. } => true,
+                _ => false,
+            };
+            let left_parens: bool = needs_parens(left, Associativity::Left);
+            let right_parens: bool = needs_parens(right, Associativity::Right);
+
+            write_operand(out, &left.node, indent, left_parens);
+            out.push(' ');
+            out.push_str(&operator.to_string());
+            out.push(' ');
+            write_operand(out, &right.node, indent, right_parens);
+        }
+        Expression::Unary { operator, operand } => {
+            out.push_str(&operator.to_string());
+            // `- -x` must not be rendered as the decrement `--x`
+            let parenthesize: bool = matches!(
+                operand.node,
+                Expression::Binary { .. }
+                    | Expression::Lambda { .. }
+                    | Expression::Unary {
+                        operator: UnaryOperator::Negate,
+                        ..
+                    }
+            );
+            write_operand(out, &operand.node, indent, parenthesize);
+        }
+        Expression::Call {
+            callee,
+            arguments,
+            named_arguments,
+        } => {
+            write_postfix_object(out, &callee.node, indent);
+            write_arguments(out, arguments, named_arguments, indent);
+        }
+        Expression::ArrayLiteral(elements) => {
+            out.push('[');
+            write_list(out, elements, indent);
+            out.push(']');
+        }
+        Expression::Tuple(elements) => {
+            out.push('(');
+            write_list(out, elements, indent);
+            out.push(')');
+        }
+        Expression::MapLiteral(entries) => {
+            out.push('{');
+            for (i, (key, value)) in entries.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write_expression(out, &key.node, indent);
+                out.push_str(": ");
+                write_expression(out, &value.node, indent);
+            }
+            out.push('}');
+        }
+        Expression::Index { object, index } => {
+            write_postfix_object(out, &object.node, indent);
+            out.push('[');
+            write_expression(out, &index.node, indent);
+            out.push(']');
+        }
+        Expression::MemberAccess { object, member } => {
+            write_postfix_object(out, &object.node, indent);
+            out.push('.');
+            out.push_str(member);
+        }
+        Expression::Self_ => out.push_str("self"),
+        Expression::Lambda { parameters, body } => {
+            out.push('|');
+            write_parameters(out, parameters);
+            out.push_str("| ");
+            if let [
+                Spanned {
+                    node: Statement::Return(Some(value)),
+                    ..
+                },
+            ] = body.as_slice()
+                && !matches!(value.node, Expression::MapLiteral(_))
+            {
+                // A map literal body would be read back as a block body.
+                write_expression(out, &value.node, indent);
+            } else {
+                write_block(out, body, indent);
+            }
+        }
+    }
+}
+
+/// Traverses the AST by reference.
+///
+/// Every `visit_*` method defaults to the matching `walk_*` function, which visits the children of
+/// the node, so implementors only override the nodes they are interested in and call the `walk_*`
+/// function themselves to keep descending.
+pub trait Visitor {
+    /// Visits the whole program.
+    fn visit_program(&mut self, program: &Program) {
+        walk_program(self, program);
+    }
+
+    /// Visits a statement.
+    fn visit_statement(&mut self, statement: &Stmt) {
+        walk_statement(self, statement);
+    }
+
+    /// Visits an expression.
+    fn visit_expression(&mut self, expression: &Expr) {
+        walk_expression(self, expression);
+    }
+}
+
+/// Visits every top-level statement of the program.
+pub fn walk_program<V: Visitor + ?Sized>(visitor: &mut V, program: &Program) {
+    for statement in &program.statements {
+        visitor.visit_statement(statement);
+    }
+}
+
+/// Visits the statements and expressions directly contained in the statement, in source order.
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, statement: &Stmt) {
+    match &statement.node {
+        Statement::VariableDeclaration { value, .. }
+        | Statement::FieldDeclaration { value, .. }
+        | Statement::Return(value) => {
+            if let Some(value) = value {
+                visitor.visit_expression(value);
+            }
+        }
+        Statement::DestructuringDeclaration { value, .. }
+        | Statement::ConstDeclaration { value, .. }
+        | Statement::Throw(value) => visitor.visit_expression(value),
+        Statement::Assignment { assignee, value } => {
+            visitor.visit_expression(assignee);
+            visitor.visit_expression(value);
+        }
+        Statement::FunctionDeclaration { body, .. }
+        | Statement::ClassDeclaration { body, .. }
+        | Statement::MethodDeclaration { body, .. } => {
+            for statement in body {
+                visitor.visit_statement(statement);
+            }
+        }
+        Statement::If {
+            conditional_branches,
+            else_branch,
+        } => {
+            for (condition, body) in conditional_branches {
+                visitor.visit_expression(condition);
+                for statement in body {
+                    visitor.visit_statement(statement);
+                }
+            }
+            for statement in else_branch.iter().flatten() {
+                visitor.visit_statement(statement);
+            }
+        }
+        Statement::While { condition, body } => {
+            visitor.visit_expression(condition);
+            for statement in body {
+                visitor.visit_statement(statement);
+            }
+        }
+        Statement::DoWhile { body, condition } => {
+            for statement in body {
+                visitor.visit_statement(statement);
+            }
+            visitor.visit_expression(condition);
+        }
+        Statement::Try { body, handler, .. } => {
+            for statement in body.iter().chain(handler) {
+                visitor.visit_statement(statement);
+            }
+        }
+        Statement::Expression(expr) => visitor.visit_expression(expr),
+        Statement::EnumDeclaration { .. }
+        | Statement::InterfaceDeclaration { .. }
+        | Statement::Import(_)
+        | Statement::Break
+        | Statement::Continue => {}
+    }
+}
+
+/// Visits the expressions and statements directly contained in the expression, in source order.
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &Expr) {
+    match &expression.node {
+        Expression::Binary { left, right, .. } => {
+            visitor.visit_expression(left);
+            visitor.visit_expression(right);
+        }
+        Expression::Unary { operand, .. } => visitor.visit_expression(operand),
+        Expression::Call {
+            callee,
+            arguments,
+            named_arguments,
+        } => {
+            visitor.visit_expression(callee);
+            for argument in arguments {
+                visitor.visit_expression(argument);
+            }
+            for (_, argument) in named_arguments {
+                visitor.visit_expression(argument);
+            }
+        }
+        Expression::ArrayLiteral(elements) | Expression::Tuple(elements) => {
+            for element in elements {
+                visitor.visit_expression(element);
+            }
+        }
+        Expression::MapLiteral(entries) => {
+            for (key, value) in entries {
+                visitor.visit_expression(key);
+                visitor.visit_expression(value);
+            }
+        }
+        Expression::Index { object, index } => {
+            visitor.visit_expression(object);
+            visitor.visit_expression(index);
+        }
+        Expression::MemberAccess { object, .. } => visitor.visit_expression(object),
+        Expression::Lambda { body, .. } => {
+            for statement in body {
+                visitor.visit_statement(statement);
+            }
+        }
+        Expression::Literal(_) | Expression::Identifier(_) | Expression::Self_ => {}
+    }
+}
+
+/// Traverses the AST by mutable reference, allowing nodes to be rewritten in place. Works like
+/// [`Visitor`], with the `walk_*_mut` functions as defaults.
+pub trait MutVisitor {
+    /// Visits the whole program.
+    fn visit_program_mut(&mut self, program: &mut Program) {
+        walk_program_mut(self, program);
+    }
+
+    /// Visits a statement.
+    fn visit_statement_mut(&mut self, statement: &mut Stmt) {
+        walk_statement_mut(self, statement);
+    }
+
+    /// Visits an expression.
+    fn visit_expression_mut(&mut self, expression: &mut Expr) {
+        walk_expression_mut(self, expression);
+    }
+}
+
+/// Visits every top-level statement of the program.
+pub fn walk_program_mut<V: MutVisitor + ?Sized>(visitor: &mut V, program: &mut Program) {
+    for statement in &mut program.statements {
+        visitor.visit_statement_mut(statement);
+    }
+}
+
+/// Visits the statements and expressions directly contained in the statement, in source order.
+pub fn walk_statement_mut<V: MutVisitor + ?Sized>(visitor: &mut V, statement: &mut Stmt) {
+    match &mut statement.node {
+        Statement::VariableDeclaration { value, .. }
+        | Statement::FieldDeclaration { value, .. }
+        | Statement::Return(value) => {
+            if let Some(value) = value {
+                visitor.visit_expression_mut(value);
+            }
+        }
+        Statement::DestructuringDeclaration { value, .. }
+        | Statement::ConstDeclaration { value, .. }
+        | Statement::Throw(value) => visitor.visit_expression_mut(value),
+        Statement::Assignment { assignee, value } => {
+            visitor.visit_expression_mut(assignee);
+            visitor.visit_expression_mut(value);
+        }
+        Statement::FunctionDeclaration { body, .. }
+        | Statement::ClassDeclaration { body, .. }
+        | Statement::MethodDeclaration { body, .. } => {
+            for statement in body {
+                visitor.visit_statement_mut(statement);
+            }
+        }
+        Statement::If {
+            conditional_branches,
+            else_branch,
+        } => {
+            for (condition, body) in conditional_branches {
+                visitor.visit_expression_mut(condition);
+                for statement in body {
+                    visitor.visit_statement_mut(statement);
+                }
+            }
+            for statement in else_branch.iter_mut().flatten() {
+                visitor.visit_statement_mut(statement);
+            }
+        }
+        Statement::While { condition, body } => {
+            visitor.visit_expression_mut(condition);
+            for statement in body {
+                visitor.visit_statement_mut(statement);
+            }
+        }
+        Statement::DoWhile { body, condition } => {
+            for statement in body {
+                visitor.visit_statement_mut(statement);
+            }
+            visitor.visit_expression_mut(condition);
+        }
+        Statement::Try { body, handler, .. } => {
+            for statement in body.iter_mut().chain(handler) {
+                visitor.visit_statement_mut(statement);
+            }
+        }
+        Statement::Expression(expr) => visitor.visit_expression_mut(expr),
+        Statement::EnumDeclaration { .. }
+        | Statement::InterfaceDeclaration { .. }
+        | Statement::Import(_)
+        | Statement::Break
+        | Statement::Continue => {}
+    }
+}
+
+/// Visits the expressions and statements directly contained in the expression, in source order.
+pub fn walk_expression_mut<V: MutVisitor + ?Sized>(visitor: &mut V, expression: &mut Expr) {
+    match &mut expression.node {
+        Expression::Binary { left, right, .. } => {
+            visitor.visit_expression_mut(left);
+            visitor.visit_expression_mut(right);
+        }
+        Expression::Unary { operand, .. } => visitor.visit_expression_mut(operand),
+        Expression::Call {
+            callee,
+            arguments,
+            named_arguments,
+        } => {
+            visitor.visit_expression_mut(callee);
+            for argument in arguments {
+                visitor.visit_expression_mut(argument);
+            }
+            for (_, argument) in named_arguments {
+                visitor.visit_expression_mut(argument);
+            }
+        }
+        Expression::ArrayLiteral(elements) | Expression::Tuple(elements) => {
+            for element in elements {
+                visitor.visit_expression_mut(element);
+            }
+        }
+        Expression::MapLiteral(entries) => {
+            for (key, value) in entries {
+                visitor.visit_expression_mut(key);
+                visitor.visit_expression_mut(value);
+            }
+        }
+        Expression::Index { object, index } => {
+            visitor.visit_expression_mut(object);
+            visitor.visit_expression_mut(index);
+        }
+        Expression::MemberAccess { object, .. } => visitor.visit_expression_mut(object),
+        Expression::Lambda { body, .. } => {
+            for statement in body {
+                visitor.visit_statement_mut(statement);
+            }
+        }
+        Expression::Literal(_) | Expression::Identifier(_) | Expression::Self_ => {}
+    }
+}
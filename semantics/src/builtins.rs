@@ -4,17 +4,19 @@ use std::collections::HashMap;
 
 use crate::types::{Class, Function, Type};
 
+/// Builds the methods of a builtin class, grouping overloads by name. The macro is private to
+/// this module, so the example can't run as a doctest; `semantics_tests` checks its output.
+///
 /// # Example
-/// ```
+/// ```text
 /// functions![
 ///     /* non-static function */
-///     somefunc(String, Int) => String,
+///     String somefunc(String, Int),
 ///     /* static function, how it's supposed to be used for readability */
-///     somefunc(Int, String) => String #static,
-///     /* In reality, anything except another # can follow the #, so these are valid too */
-///     somefunc(Int, Int) => String #123,
-///     somefunc(String, String) => String #,,
-///     somefunc(Boolean) => String #/,
+///     String somefunc(Int, String) #static,
+///     /* In reality, any single token can follow the #, so these are valid too */
+///     String somefunc(Int, Int) #123,
+///     String somefunc(Boolean) #/,
 /// ]
 /// ```
 macro_rules! functions {
@@ -29,6 +31,7 @@ macro_rules! functions {
             .or_default()
             .push(Function {
                 parameters: vec![$(Type::$parameter_type),*],
+                parameter_names: Vec::new(),
                 return_type: Type::$return_type,
                 is_static: functions!(@s $($is_static)?),
             });
@@ -70,6 +73,21 @@ fn builtin_builtin() -> Class {
             Void println(Int) #static,
             Void println(Float) #static,
 
+            String readLine() #static,
+            Int readInt() #static,
+            Float readFloat() #static,
+
+            Int argCount() #static,
+            String arg(Int) #static,
+
+            Void exit(Int) #static,
+
+            Void assert(Boolean) #static,
+            Void assertEq(String, String) #static,
+            Void assertEq(Boolean, Boolean) #static,
+            Void assertEq(Int, Int) #static,
+            Void assertEq(Float, Float) #static,
+
             String parseString(Boolean) #static,
             String parseString(Int) #static,
             String parseString(Float) #static,
@@ -87,6 +105,7 @@ fn builtin_builtin() -> Class {
             Float parseFloat(Int) #static,
         ],
         fields: HashMap::new(),
+        superclass: None,
     }
 }
 
@@ -94,6 +113,7 @@ fn builtin_string() -> Class {
     Class {
         name: "string".into(),
         methods: functions![
+            String toString(),
             Boolean toBool(),
             Int toInt(),
             Float toFloat(),
@@ -105,6 +125,7 @@ fn builtin_string() -> Class {
             Boolean _bopNe(String),
         ],
         fields: HashMap::new(),
+        superclass: None,
     }
 }
 
@@ -118,12 +139,11 @@ fn builtin_bool() -> Class {
 
             Boolean _bopEq(Boolean),
             Boolean _bopNe(Boolean),
-            Boolean _bopAnd(Boolean),
-            Boolean _bopOr(Boolean),
 
             Boolean _uopNot(),
         ],
         fields: HashMap::new(),
+        superclass: None,
     }
 }
 
@@ -139,6 +159,8 @@ fn builtin_int() -> Class {
             Int _bopSub(Int),
             Int _bopMul(Int),
             Int _bopDiv(Int),
+            Int _bopMod(Int),
+            Int _bopPow(Int),
             Boolean _bopEq(Int),
             Boolean _bopNe(Int),
             Boolean _bopLt(Int),
@@ -150,14 +172,19 @@ fn builtin_int() -> Class {
             Float _bopSub(Float),
             Float _bopMul(Float),
             Float _bopDiv(Float),
+            Float _bopMod(Float),
+            Float _bopPow(Float),
             Boolean _bopEq(Float),
             Boolean _bopNe(Float),
             Boolean _bopLt(Float),
             Boolean _bopGt(Float),
             Boolean _bopLe(Float),
             Boolean _bopGe(Float),
+
+            Int _uopNeg(),
         ],
         fields: HashMap::new(),
+        superclass: None,
     }
 }
 
@@ -173,6 +200,8 @@ fn builtin_float() -> Class {
             Float _bopSub(Float),
             Float _bopMul(Float),
             Float _bopDiv(Float),
+            Float _bopMod(Float),
+            Float _bopPow(Float),
             Boolean _bopEq(Float),
             Boolean _bopNe(Float),
             Boolean _bopLt(Float),
@@ -184,13 +213,18 @@ fn builtin_float() -> Class {
             Float _bopSub(Int),
             Float _bopMul(Int),
             Float _bopDiv(Int),
+            Float _bopMod(Int),
+            Float _bopPow(Int),
             Boolean _bopEq(Int),
             Boolean _bopNe(Int),
             Boolean _bopLt(Int),
             Boolean _bopGt(Int),
             Boolean _bopLe(Int),
             Boolean _bopGe(Int),
+
+            Float _uopNeg(),
         ],
         fields: HashMap::new(),
+        superclass: None,
     }
 }
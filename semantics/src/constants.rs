@@ -0,0 +1,165 @@
+//! Contains the evaluator for compile-time constant expressions, and the pass that folds them
+//! before code generation.
+
+use std::cmp::Ordering;
+
+use parser::types::{
+    BinaryOperator, Expr, Expression, Literal, MutVisitor, Program, UnaryOperator,
+    walk_expression_mut,
+};
+
+/// The longest string a repetition is folded to. Longer ones are left to the runtime, so a large
+/// count can't exhaust the compiler's memory or bloat the generated code.
+const MAX_REPEATED_LENGTH: usize = 4096;
+
+/// Represents a value computed at compile time.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstantValue {
+    /// An integer value.
+    Int(i64),
+    /// A floating-point value.
+    Float(f64),
+    /// A boolean value.
+    Boolean(bool),
+    /// A string value.
+    String(String),
+}
+
+/// Folds the given expression to its value if it only consists of literals and the operators
+/// applied to them.
+///
+/// Returns `None` if the expression contains anything else, or if its value can't be known at
+/// compile time, e.g. because of a division by zero or an integer overflow.
+#[must_use]
+pub fn evaluate(expr: &Expr) -> Option<ConstantValue> {
+    match &expr.node {
+        Expression::Literal(literal) => match literal {
+            Literal::Integer(value) => Some(ConstantValue::Int(*value)),
+            Literal::Float(value) => Some(ConstantValue::Float(*value)),
+            Literal::Boolean(value) => Some(ConstantValue::Boolean(*value)),
+            Literal::String(value) => Some(ConstantValue::String(value.clone())),
+            Literal::Null => None,
+        },
+        Expression::Unary { operator, operand } => match (operator, evaluate(operand)?) {
+            (UnaryOperator::Not, ConstantValue::Boolean(value)) => {
+                Some(ConstantValue::Boolean(!value))
+            }
+            (UnaryOperator::Negate, ConstantValue::Int(value)) => {
+                Some(ConstantValue::Int(integer(value.checked_neg()?)?))
+            }
+            (UnaryOperator::Negate, ConstantValue::Float(value)) => {
+                Some(ConstantValue::Float(-value))
+            }
+            _ => None,
+        },
+        Expression::Binary {
+            left,
+            operator,
+            right,
+        } => binary(operator, evaluate(left)?, evaluate(right)?),
+        _ => None,
+    }
+}
+
+/// Replaces every constant expression in the program with the literal of its value, e.g.
+/// `2 * 3 + x` becomes `6 + x`. Must only run on programs that passed semantic analysis.
+///
+/// Expressions whose value can't be known at compile time are kept, so they behave the same at
+/// runtime, e.g. a division by zero still fails there.
+pub fn fold(program: &mut Program) {
+    Folder.visit_program_mut(program);
+}
+
+struct Folder;
+
+impl MutVisitor for Folder {
+    fn visit_expression_mut(&mut self, expression: &mut Expr) {
+        // Operands are folded first, so only the innermost operator has to be evaluated
+        walk_expression_mut(self, expression);
+
+        if matches!(expression.node, Expression::Literal(_)) {
+            return;
+        }
+
+        let literal: Literal = match evaluate(expression) {
+            Some(ConstantValue::Int(value)) => Literal::Integer(value),
+            Some(ConstantValue::Float(value)) if value.is_finite() => Literal::Float(value),
+            Some(ConstantValue::Boolean(value)) => Literal::Boolean(value),
+            Some(ConstantValue::String(value)) => Literal::String(value),
+            _ => return,
+        };
+        expression.node = Expression::Literal(literal);
+    }
+}
+
+fn binary(
+    operator: &BinaryOperator,
+    left: ConstantValue,
+    right: ConstantValue,
+) -> Option<ConstantValue> {
+    use ConstantValue::{Boolean, Float, Int, String};
+
+    let ordering: Option<Ordering> = compare(&left, &right);
+
+    Some(match (operator, left, right) {
+        (BinaryOperator::Add, Int(l), Int(r)) => Int(integer(l.checked_add(r)?)?),
+        (BinaryOperator::Subtract, Int(l), Int(r)) => Int(integer(l.checked_sub(r)?)?),
+        (BinaryOperator::Multiply, Int(l), Int(r)) => Int(integer(l.checked_mul(r)?)?),
+        (BinaryOperator::Divide, Int(l), Int(r)) => Int(integer(l.checked_div(r)?)?),
+        (BinaryOperator::Modulo, Int(l), Int(r)) => Int(integer(l.checked_rem(r)?)?),
+        (BinaryOperator::Power, Int(l), Int(r)) => {
+            Int(integer(l.checked_pow(r.try_into().ok()?)?)?)
+        }
+        (BinaryOperator::Add, Float(l), Float(r)) => Float(l + r),
+        (BinaryOperator::Subtract, Float(l), Float(r)) => Float(l - r),
+        (BinaryOperator::Multiply, Float(l), Float(r)) => Float(l * r),
+        (BinaryOperator::Divide, Float(l), Float(r)) => Float(l / r),
+        (BinaryOperator::Modulo, Float(l), Float(r)) => Float(l % r),
+        (BinaryOperator::Power, Float(l), Float(r)) => Float(l.powf(r)),
+        (BinaryOperator::Add, String(l), String(r)) => String(l + &r),
+        (BinaryOperator::Multiply, String(l), Int(r)) => {
+            let count: usize = r.try_into().ok()?;
+            if l.len().checked_mul(count)? > MAX_REPEATED_LENGTH {
+                return None;
+            }
+            String(l.repeat(count))
+        }
+        (BinaryOperator::Divide, String(l), String(r)) => String(l + "/" + &r),
+        (BinaryOperator::And, Boolean(l), Boolean(r)) => Boolean(l && r),
+        (BinaryOperator::Or, Boolean(l), Boolean(r)) => Boolean(l || r),
+        (BinaryOperator::Equals, ..) => Boolean(ordering? == Ordering::Equal),
+        (BinaryOperator::NotEquals, ..) => Boolean(ordering? != Ordering::Equal),
+        (BinaryOperator::LessThan, Int(_), Int(_))
+        | (BinaryOperator::LessThan, Float(_), Float(_)) => Boolean(ordering? == Ordering::Less),
+        (BinaryOperator::GreaterThan, Int(_), Int(_))
+        | (BinaryOperator::GreaterThan, Float(_), Float(_)) => {
+            Boolean(ordering? == Ordering::Greater)
+        }
+        (BinaryOperator::LessThanOrEqual, Int(_), Int(_))
+        | (BinaryOperator::LessThanOrEqual, Float(_), Float(_)) => {
+            Boolean(ordering? != Ordering::Greater)
+        }
+        (BinaryOperator::GreaterThanOrEqual, Int(_), Int(_))
+        | (BinaryOperator::GreaterThanOrEqual, Float(_), Float(_)) => {
+            Boolean(ordering? != Ordering::Less)
+        }
+        _ => return None,
+    })
+}
+
+/// Compares two values of the same type. Floats that are NaN can't be compared.
+fn compare(left: &ConstantValue, right: &ConstantValue) -> Option<Ordering> {
+    match (left, right) {
+        (ConstantValue::Int(l), ConstantValue::Int(r)) => Some(l.cmp(r)),
+        (ConstantValue::Float(l), ConstantValue::Float(r)) => l.partial_cmp(r),
+        (ConstantValue::Boolean(l), ConstantValue::Boolean(r)) => Some(l.cmp(r)),
+        (ConstantValue::String(l), ConstantValue::String(r)) => Some(l.cmp(r)),
+        _ => None,
+    }
+}
+
+/// Rejects integer results outside the range of the runtime's 32-bit integers, which would wrap
+/// around at runtime.
+fn integer(value: i64) -> Option<i64> {
+    i32::try_from(value).ok().map(i64::from)
+}
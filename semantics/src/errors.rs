@@ -1,7 +1,12 @@
 //! Contains the different errors that can come up during semantic analysis
 
+use std::fmt::{self, Display, Formatter};
+
+use parser::errors::with_source;
+
 /// Represents an error that can occur during semantic analysis, including the type of error and
 /// the location in the source code where the error occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SemanticError {
     /// The type of semantic error that occurred.
     pub error_type: SemanticErrorType,
@@ -29,14 +34,30 @@ impl SemanticError {
         message
     }
 
+    /// Returns the full error message, followed by the offending line of `source` with a caret
+    /// marking the column of the error.
+    #[must_use]
+    pub fn error_message_with_source(&self, source: &str) -> String {
+        with_source(self.error_message(), source, self.line, self.column)
+    }
+
     /// Prints the error message to stderr.
     pub fn print(&self) {
         eprintln!("{}", self.error_message());
     }
 }
 
+impl Display for SemanticError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.error_message())
+    }
+}
+
+impl std::error::Error for SemanticError {}
+
 /// Represents an error that can occur during semantic analysis, such as type errors or scope
 /// resolution
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SemanticErrorType {
     /// User tried to create a function or class with the same name as an existing variable in the
     /// current scope.
@@ -103,6 +124,8 @@ pub enum SemanticErrorType {
     MethodFieldNameConflict(String),
     /// User used a non-boolean expression as the condition in an if or while statement..
     NonBooleanCondition(String),
+    /// User tried to use a non-boolean expression as an operand of `&&` or `||`.
+    NonBooleanOperand(String),
     /// User tried to declare a function outside global scope, e.g. in an if statement.
     IllegalFunctionDeclaration(String),
     /// User tried to declare a class outside global scope, e.g. in an if statement.
@@ -117,6 +140,10 @@ pub enum SemanticErrorType {
     },
     /// User tried to return outside a function body.
     IllegalReturn,
+    /// User used `break` or `continue` outside of a loop.
+    IllegalBreak(String),
+    /// User tried to throw a value that isn't a string.
+    NonStringThrow(String),
     /// User tried to declare a method with a non-allowed name, e.g. `new`.
     IllegalMethodName(String),
     /// Initialization of semantic analyzer threw an error
@@ -134,6 +161,17 @@ pub enum SemanticErrorType {
         /// The actual types of the arguments that were passed in the function call.
         found: Vec<String>,
     },
+    /// User tried to call a function or method with the wrong number of arguments.
+    ArgumentCountMismatch {
+        /// The name of the function or method that was being called.
+        func: String,
+        /// The number of parameters the function or method declares.
+        expected: usize,
+        /// The number of arguments that were passed in the call.
+        found: usize,
+    },
+    /// User used the result of a call to a void function or method as a value.
+    VoidValue(String),
     /// User tried to call a method with arguments that no overload of the method accepts.
     MethodOverloadNotFound {
         /// The class the method belongs to.
@@ -143,6 +181,37 @@ pub enum SemanticErrorType {
         /// The given argument types that didn't match any overload of the method.
         argument_types: Vec<String>,
     },
+    /// User called an overloaded method with arguments that several overloads accept, none of
+    /// which is more specific than the others.
+    AmbiguousMethodCall {
+        /// The class the method belongs to.
+        class: String,
+        /// The name of the method that was being called.
+        method: String,
+        /// The given argument types.
+        argument_types: Vec<String>,
+    },
+    /// User passed a named argument that doesn't match any parameter of the called function.
+    UnknownParameter {
+        /// The name of the function that was being called.
+        func: String,
+        /// The name of the argument.
+        parameter: String,
+    },
+    /// User passed more than one argument for the same parameter.
+    DuplicateArgument {
+        /// The name of the function that was being called.
+        func: String,
+        /// The name of the parameter.
+        parameter: String,
+    },
+    /// User skipped a parameter that comes before a named argument.
+    MissingArgument {
+        /// The name of the function that was being called.
+        func: String,
+        /// The name of the parameter.
+        parameter: String,
+    },
     /// User tried to declare a variable with type void.
     IllegalVoidVariable(String),
     /// User tried to declare a field with type void.
@@ -153,6 +222,30 @@ pub enum SemanticErrorType {
     EntryPointReturnTypeMismatch(String),
     /// User declared the "main" method as an instance method instead of a static method.
     EntryPointMustBeStatic,
+    /// User used a language feature that the parser accepts but semantic analysis does not
+    /// support yet.
+    UnsupportedFeature(String),
+    /// User tried to initialize a constant with a value that is not a compile-time constant.
+    NonConstantValue(String),
+    /// User tried to assign to a constant variable or field after its declaration.
+    ConstantReassignment(String),
+    /// User divided an integer or took its remainder by an expression that is always zero.
+    DivisionByZero,
+    /// User tried to inherit from a builtin type.
+    IllegalSuperclass(String),
+    /// User overrode an inherited method with a different return type or staticness.
+    IllegalOverride(String),
+    /// User used `self` or `Self` outside of a class.
+    SelfOutsideClass(String),
+    /// User declared a variable without a type and without a value to infer it from.
+    MissingTypeAnnotation(String),
+    /// User mixed an `int` and a `float` operand while numeric coercion is disabled.
+    ImplicitNumericConversion {
+        /// The type of the left operand.
+        left: String,
+        /// The type of the right operand.
+        right: String,
+    },
 }
 
 impl SemanticErrorType {
@@ -260,6 +353,11 @@ impl SemanticErrorType {
                 found,
                 "as the condition in an if or while statement",
             ),
+            Self::NonBooleanOperand(found) => Self::one_var_message(
+                "Tried to use non-boolean expression of type",
+                found,
+                "as an operand of a logical operator",
+            ),
             Self::IllegalFunctionDeclaration(func) => Self::one_var_message(
                 "Cannot declare function",
                 func,
@@ -278,6 +376,14 @@ impl SemanticErrorType {
                 "",
             ),
             Self::IllegalReturn => "Tried to return a value outside of a function body".to_string(),
+            Self::IllegalBreak(keyword) => {
+                Self::one_var_message("Cannot use", keyword, "outside of a loop")
+            }
+            Self::NonStringThrow(found) => Self::one_var_message(
+                "Tried to throw a value of type",
+                found,
+                "but only strings can be thrown",
+            ),
             Self::IllegalMethodName(name) => Self::one_var_message(
                 "Cannot declare method",
                 name,
@@ -309,6 +415,16 @@ impl SemanticErrorType {
                 found.join(", ").as_str(),
                 "",
             ),
+            Self::ArgumentCountMismatch {
+                func,
+                expected,
+                found,
+            } => format!("Tried to call '{func}' with {found} arguments, but it takes {expected}"),
+            Self::VoidValue(func) => Self::one_var_message(
+                "Cannot use the result of",
+                func,
+                "as a value because it returns void",
+            ),
             Self::MethodOverloadNotFound {
                 class,
                 method,
@@ -322,6 +438,36 @@ impl SemanticErrorType {
                 argument_types.join(", ").as_str(),
                 "but no overload of the method accepts this.",
             ),
+            Self::AmbiguousMethodCall {
+                class,
+                method,
+                argument_types,
+            } => Self::three_var_message(
+                "Call to method",
+                method,
+                "of class",
+                class,
+                "with arguments of type",
+                argument_types.join(", ").as_str(),
+                "is ambiguous between several overloads.",
+            ),
+            Self::UnknownParameter { func, parameter } => {
+                Self::two_var_message("Function", func, "has no parameter named", parameter, "")
+            }
+            Self::DuplicateArgument { func, parameter } => Self::two_var_message(
+                "Parameter",
+                parameter,
+                "of function",
+                func,
+                "was given more than one argument",
+            ),
+            Self::MissingArgument { func, parameter } => Self::two_var_message(
+                "Parameter",
+                parameter,
+                "of function",
+                func,
+                "was not given an argument",
+            ),
             Self::IllegalVoidVariable(var) => Self::one_var_message(
                 "Cannot declare variable",
                 var,
@@ -345,6 +491,43 @@ impl SemanticErrorType {
             Self::EntryPointMustBeStatic => {
                 "Entry point 'main()' method must be declared as static".to_string()
             }
+            Self::UnsupportedFeature(feature) => Self::one_var_message(
+                "The language feature",
+                feature,
+                "is not supported by the semantic analyzer yet",
+            ),
+            Self::NonConstantValue(name) => Self::one_var_message(
+                "Cannot declare constant",
+                name,
+                "because its value is not a compile-time constant",
+            ),
+            Self::ConstantReassignment(name) => {
+                Self::one_var_message("Cannot assign to", name, "because it is a constant")
+            }
+            Self::DivisionByZero => String::from("Integer division or modulo by zero"),
+            Self::IllegalSuperclass(class) => {
+                Self::one_var_message("Cannot inherit from", class, "because it is a builtin type")
+            }
+            Self::IllegalOverride(method) => Self::one_var_message(
+                "Cannot override method",
+                method,
+                "with a different return type or staticness than the inherited method",
+            ),
+            Self::SelfOutsideClass(keyword) => {
+                Self::one_var_message("Cannot use", keyword, "outside of a class")
+            }
+            Self::MissingTypeAnnotation(var) => Self::one_var_message(
+                "Cannot infer the type of variable",
+                var,
+                "without an initial value",
+            ),
+            Self::ImplicitNumericConversion { left, right } => Self::two_var_message(
+                "Cannot implicitly convert between",
+                left,
+                "and",
+                right,
+                "operands, convert one of them with toFloat() or toInt()",
+            ),
         }
     }
 
@@ -398,21 +581,195 @@ impl SemanticErrorType {
             Self::DuplicateMethod(_) => "DuplicateMethod",
             Self::MethodFieldNameConflict(_) => "MethodFieldNameConflict",
             Self::NonBooleanCondition(_) => "NonBooleanCondition",
+            Self::NonBooleanOperand(_) => "NonBooleanOperand",
             Self::IllegalFunctionDeclaration(_) => "IllegalFunctionDeclaration",
             Self::IllegalClassDeclaration(_) => "IllegalClassDeclaration",
             Self::ReturnTypeMismatch { .. } => "ReturnTypeMismatch",
             Self::IllegalReturn => "IllegalReturn",
+            Self::IllegalBreak(_) => "IllegalBreak",
+            Self::NonStringThrow(_) => "NonStringThrow",
             Self::IllegalMethodName(_) => "IllegalMethodName",
             Self::InternalInitializationError(_) => "Internal: InitializationError",
             Self::FieldAfterMethod(_) => "FieldAfterMethod",
             Self::MissingReturn => "MissingReturn",
             Self::ArgumentTypeMismatch { .. } => "ArgumentTypeMismatch",
+            Self::ArgumentCountMismatch { .. } => "ArgumentCountMismatch",
+            Self::VoidValue(_) => "VoidValue",
             Self::MethodOverloadNotFound { .. } => "MethodOverloadNotFound",
+            Self::AmbiguousMethodCall { .. } => "AmbiguousMethodCall",
+            Self::UnknownParameter { .. } => "UnknownParameter",
+            Self::DuplicateArgument { .. } => "DuplicateArgument",
+            Self::MissingArgument { .. } => "MissingArgument",
             Self::IllegalVoidVariable(_) => "IllegalVoidVariable",
             Self::IllegalVoidField(_) => "IllegalVoidField",
             Self::EntryPointMissing => "EntryPointMissing",
             Self::EntryPointReturnTypeMismatch(_) => "EntryPointReturnTypeMismatch",
             Self::EntryPointMustBeStatic => "EntryPointMustBeStatic",
+            Self::UnsupportedFeature(_) => "UnsupportedFeature",
+            Self::NonConstantValue(_) => "NonConstantValue",
+            Self::ConstantReassignment(_) => "ConstantReassignment",
+            Self::DivisionByZero => "DivisionByZero",
+            Self::IllegalSuperclass(_) => "IllegalSuperclass",
+            Self::IllegalOverride(_) => "IllegalOverride",
+            Self::SelfOutsideClass(_) => "SelfOutsideClass",
+            Self::MissingTypeAnnotation(_) => "MissingTypeAnnotation",
+            Self::ImplicitNumericConversion { .. } => "ImplicitNumericConversion",
+        }
+    }
+}
+
+/// The severity of a diagnostic. Only errors fail the compilation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// A problem that makes the program invalid.
+    Error,
+    /// A likely mistake that still results in a valid program.
+    Warning,
+    /// Additional information that is not a problem by itself.
+    Note,
+}
+
+impl Severity {
+    /// Returns the prefix used when printing a diagnostic of this severity.
+    #[must_use]
+    pub const fn prefix(self) -> &'static str {
+        match self {
+            Self::Error => "SemanticError",
+            Self::Warning => "SemanticWarning",
+            Self::Note => "SemanticNote",
+        }
+    }
+}
+
+/// Represents a diagnostic reported during semantic analysis, including its severity and the
+/// location in the source code it refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The severity of the diagnostic.
+    pub severity: Severity,
+    /// The name of the error or warning type, e.g. `UnreachableCode`.
+    pub name: &'static str,
+    /// The human-readable message describing the diagnostic.
+    pub message: String,
+    /// The line number in the source code the diagnostic refers to.
+    pub line: usize,
+    /// The column number in the source code the diagnostic refers to.
+    pub column: usize,
+}
+
+impl Diagnostic {
+    /// Creates a warning diagnostic of the given type at the given location.
+    #[must_use]
+    pub fn warning(warning_type: &WarningType, loc: (usize, usize)) -> Self {
+        Self {
+            severity: Severity::Warning,
+            name: warning_type.warning_name(),
+            message: warning_type.message(),
+            line: loc.0,
+            column: loc.1,
+        }
+    }
+
+    /// Creates a note diagnostic with the given name and message at the given location.
+    #[must_use]
+    pub const fn note(name: &'static str, message: String, loc: (usize, usize)) -> Self {
+        Self {
+            severity: Severity::Note,
+            name,
+            message,
+            line: loc.0,
+            column: loc.1,
+        }
+    }
+
+    /// Returns the full diagnostic message.
+    #[must_use]
+    pub fn diagnostic_message(&self) -> String {
+        format!(
+            "{}: {} at [{}:{}]: {}",
+            self.severity.prefix(),
+            self.name,
+            self.line,
+            self.column,
+            self.message
+        )
+    }
+
+    /// Returns the full diagnostic message, followed by the line of `source` it refers to with a
+    /// caret marking the column.
+    #[must_use]
+    pub fn diagnostic_message_with_source(&self, source: &str) -> String {
+        with_source(self.diagnostic_message(), source, self.line, self.column)
+    }
+
+    /// Prints the diagnostic message to stderr.
+    pub fn print(&self) {
+        eprintln!("{}", self.diagnostic_message());
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.diagnostic_message())
+    }
+}
+
+impl From<SemanticError> for Diagnostic {
+    fn from(error: SemanticError) -> Self {
+        Self {
+            severity: Severity::Error,
+            name: error.error_type.error_name(),
+            message: error.error_type.message(),
+            line: error.line,
+            column: error.column,
+        }
+    }
+}
+
+/// Represents a warning that can occur during semantic analysis. Warnings point out likely
+/// mistakes without making the program invalid.
+pub enum WarningType {
+    /// User wrote statements after a `return`, `break` or `continue` in the same block, which can
+    /// never run.
+    UnreachableCode,
+    /// User declared a variable or parameter that is never read.
+    UnusedVariable(String),
+    /// User declared a function that is never called.
+    UnusedFunction(String),
+    /// User stored a value to a variable that is overwritten or goes out of scope before it is
+    /// read.
+    DeadStore(String),
+}
+
+impl WarningType {
+    /// Returns a human-readable message describing the warning.
+    #[must_use]
+    pub fn message(&self) -> String {
+        match self {
+            Self::UnreachableCode => String::from(
+                "Statement is unreachable because it follows a return, break, continue or \
+                     throw statement",
+            ),
+            Self::UnusedVariable(name) => format!(
+                "Variable '{name}' is never read. Prefix its name with '_' if this is intended"
+            ),
+            Self::UnusedFunction(name) => format!(
+                "Function '{name}' is never called. Prefix its name with '_' if this is intended"
+            ),
+            Self::DeadStore(name) => {
+                format!("Value assigned to variable '{name}' is never read")
+            }
+        }
+    }
+
+    /// Returns the name of the warning type as a string.
+    #[must_use]
+    pub const fn warning_name(&self) -> &'static str {
+        match self {
+            Self::UnreachableCode => "UnreachableCode",
+            Self::UnusedVariable(_) => "UnusedVariable",
+            Self::UnusedFunction(_) => "UnusedFunction",
+            Self::DeadStore(_) => "DeadStore",
         }
     }
 }
@@ -1,55 +1,117 @@
 //! The semantic analysis crate for the custom language's AST.
 
-use std::collections::{HashMap, hash_map::Entry};
+use std::collections::{HashMap, HashSet, hash_map::Entry};
 
 use parser::types::{
-    BinaryOperator, Expr, Expression, Literal, Span, Statement, Stmt, UnaryOperator,
+    BinaryOperator, Expr, Expression, Literal, Parameter, Span, Spanned, Statement, Stmt,
+    UnaryOperator,
 };
 
 use crate::{
-    errors::{SemanticError, SemanticErrorType},
+    constants::ConstantValue,
+    errors::{Diagnostic, SemanticError, SemanticErrorType, WarningType},
+    lints::LintConfig,
     types::{
-        Class, ExpressionReturn, Field, FieldDeclarationInfo, Function, LValue,
+        Class, ExpressionReturn, Field, FieldDeclarationInfo, Function, InheritedMembers, LValue,
         MethodDeclarationBodyInfo, MethodDeclarationSignatureInfo,
-        MethodDeclarationSignatureReturn, Scope, StatementReturn, Type,
+        MethodDeclarationSignatureReturn, NumericCoercion, Scope, StatementReturn, Store, Type,
     },
 };
 
 pub mod builtins;
+pub mod constants;
 pub mod errors;
+pub mod lints;
+pub mod resolve;
 pub mod types;
 
+/// A top-level statement that is left to analyze after the declarations have been hoisted.
+enum Hoisted {
+    /// A statement that is analyzed as usual.
+    Statement(Stmt),
+    /// The method bodies of a class whose fields and methods have already been declared.
+    MethodBodies(String, Vec<MethodDeclarationBodyInfo>),
+}
+
+/// Options that change how the semantic analysis checks and reports a program.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnalyzerOptions {
+    /// The levels of the lints to report.
+    pub lints: LintConfig,
+    /// Whether operators may mix `int` and `float` operands.
+    pub numeric_coercion: NumericCoercion,
+}
+
 /// Analyzes the AST for semantic correctness, such as type checking and scope resolution (later on)
 pub struct SemanticAnalyzer {
     function_return: Option<Type>,
     found_return: bool,
     class: Option<Type>,
     scope: Scope,
+    diagnostics: Vec<Diagnostic>,
+    block: usize,
+    blocks: usize,
+    in_loop: bool,
+    numeric_coercion: NumericCoercion,
 }
 
 impl SemanticAnalyzer {
-    /// Analyzes the given AST for semantic correctness.
+    /// Analyzes the given AST for semantic correctness, returning the warnings and notes found
+    /// along the way.
+    ///
+    /// # Parameters
+    /// - `ast`: The abstract syntax tree to analyze.
+    ///
+    /// # Errors
+    /// TODO: Add errors later
+    pub fn analyze(ast: parser::types::Program) -> Result<Vec<Diagnostic>, SemanticError> {
+        Self::analyze_with_options(ast, &AnalyzerOptions::default())
+    }
+
+    /// Analyzes the given AST like [`SemanticAnalyzer::analyze`], using the given options.
     ///
     /// # Parameters
     /// - `ast`: The abstract syntax tree to analyze.
+    /// - `options`: The lint levels and checks to use.
     ///
     /// # Errors
     /// TODO: Add errors later
-    pub fn analyze(ast: parser::types::Program) -> StatementReturn {
+    pub fn analyze_with_options(
+        ast: parser::types::Program,
+        options: &AnalyzerOptions,
+    ) -> Result<Vec<Diagnostic>, SemanticError> {
         let mut analyzer: Self = Self {
             scope: Scope::new(None),
             function_return: None,
             found_return: false,
             class: None,
+            diagnostics: Vec::new(),
+            block: 0,
+            blocks: 0,
+            in_loop: false,
+            numeric_coercion: options.numeric_coercion,
         };
 
         for class in builtins::get_builtin_types() {
             analyzer.scope.add_class(class, (0, 0))?;
         }
 
+        // Declare all functions and classes first, so they can be used before their declaration
+        let mut hoisted: Vec<Hoisted> = Vec::new();
         for statement in ast.statements {
-            analyzer.statement(statement, true)?;
+            hoisted.push(analyzer.hoist(statement)?);
+        }
+
+        for statement in hoisted {
+            match statement {
+                Hoisted::Statement(statement) => analyzer.statement(statement, true)?,
+                Hoisted::MethodBodies(class_name, bodies) => {
+                    analyzer.method_bodies(&class_name, bodies)?;
+                }
+            }
         }
+        let unread: Vec<Diagnostic> = Self::unread_warnings(&analyzer.scope);
+        analyzer.diagnostics.extend(unread);
 
         let main: Class = analyzer
             .scope
@@ -70,7 +132,7 @@ impl SemanticAnalyzer {
 
         if main_method.return_type == Type::Int {
             if main_method.is_static {
-                Ok(())
+                Ok(options.lints.apply(analyzer.diagnostics))
             } else {
                 Err(SemanticError {
                     error_type: SemanticErrorType::EntryPointMustBeStatic,
@@ -89,18 +151,82 @@ impl SemanticAnalyzer {
         }
     }
 
+    /// Declares the signature of a top-level function, or the fields and methods of a top-level
+    /// class, returning what is left to analyze once every declaration is known.
+    fn hoist(&mut self, stmt: Stmt) -> Result<Hoisted, SemanticError> {
+        let loc: (usize, usize) = Self::get_loc(&stmt.span);
+        match stmt.node {
+            Statement::FunctionDeclaration {
+                ref return_type,
+                ref name,
+                ref parameters,
+                ref generics,
+                ..
+            } if generics.is_empty() => {
+                self.function_signature(return_type, name, parameters, loc)?;
+                Ok(Hoisted::Statement(stmt))
+            }
+            Statement::ClassDeclaration {
+                name,
+                superclass,
+                body,
+                generics,
+                ..
+            } if generics.is_empty() => {
+                let bodies: Vec<MethodDeclarationBodyInfo> =
+                    self.class_signature(&name, superclass.as_deref(), body, loc)?;
+                Ok(Hoisted::MethodBodies(name, bodies))
+            }
+            _ => Ok(Hoisted::Statement(stmt)),
+        }
+    }
+
     fn statement(&mut self, stmt: Stmt, allows_definitions: bool) -> StatementReturn {
         let loc: (usize, usize) = Self::get_loc(&stmt.span);
         match stmt.node {
             Statement::VariableDeclaration { type_, name, value } => {
                 self.variable_declaration(&type_, &name, value, loc)
             }
+            Statement::DestructuringDeclaration { .. } => Err(SemanticError {
+                error_type: SemanticErrorType::UnsupportedFeature("tuples".into()),
+                line: loc.0,
+                column: loc.1,
+            }),
+            Statement::ConstDeclaration { type_, name, value } => {
+                self.const_declaration(&type_, &name, value, loc)
+            }
+            Statement::Import(_) => Err(SemanticError {
+                error_type: SemanticErrorType::UnsupportedFeature("imports".into()),
+                line: loc.0,
+                column: loc.1,
+            }),
+            Statement::EnumDeclaration { .. } => Err(SemanticError {
+                error_type: SemanticErrorType::UnsupportedFeature("enums".into()),
+                line: loc.0,
+                column: loc.1,
+            }),
+            Statement::InterfaceDeclaration { .. } => Err(SemanticError {
+                error_type: SemanticErrorType::UnsupportedFeature("interfaces".into()),
+                line: loc.0,
+                column: loc.1,
+            }),
             Statement::Assignment { assignee, value } => self.assignment(*assignee, value),
+            Statement::FunctionDeclaration { generics, .. }
+            | Statement::ClassDeclaration { generics, .. }
+                if !generics.is_empty() =>
+            {
+                Err(SemanticError {
+                    error_type: SemanticErrorType::UnsupportedFeature("generics".into()),
+                    line: loc.0,
+                    column: loc.1,
+                })
+            }
             Statement::FunctionDeclaration {
                 return_type,
                 name,
                 parameters,
                 body,
+                ..
             } => self.function_declaration(
                 &return_type,
                 &name,
@@ -109,8 +235,13 @@ impl SemanticAnalyzer {
                 allows_definitions,
                 loc,
             ),
-            Statement::ClassDeclaration { name, body } => {
-                self.class_declaration(&name, body, allows_definitions, loc)
+            Statement::ClassDeclaration {
+                name,
+                superclass,
+                body,
+                ..
+            } => {
+                self.class_declaration(&name, superclass.as_deref(), body, allows_definitions, loc)
             }
             Statement::FieldDeclaration { .. } | Statement::MethodDeclaration { .. } => {
                 unreachable!(
@@ -122,16 +253,169 @@ impl SemanticAnalyzer {
                 else_branch,
             } => self.if_statement(conditional_branches, else_branch, loc),
             Statement::While { condition, body } => self.while_statement(condition, body, loc),
+            Statement::DoWhile { body, condition } => self.do_while_statement(body, condition),
+            Statement::Try {
+                body,
+                error_name,
+                handler,
+            } => self.try_statement(body, &error_name, handler, loc),
+            Statement::Throw(message) => self.throw_statement(message, loc),
             Statement::Return(expr) => self.return_statement(expr, loc),
+            Statement::Break | Statement::Continue if !self.in_loop => Err(SemanticError {
+                error_type: SemanticErrorType::IllegalBreak(stmt.node.name().to_lowercase()),
+                line: loc.0,
+                column: loc.1,
+            }),
+            Statement::Break | Statement::Continue => Ok(()),
+            // Calls are the only expressions whose void result may be discarded
+            Statement::Expression(Spanned {
+                node:
+                    Expression::Call {
+                        callee,
+                        arguments,
+                        named_arguments,
+                    },
+                ..
+            }) => self.call(*callee, arguments, named_arguments).map(|_| ()),
             Statement::Expression(expr) => self.expression(expr).map(|_| ()),
         }
     }
 
+    /// Analyzes the statements of a block, warning about statements that follow a `return`,
+    /// `break`, `continue` or `throw`.
+    fn block(&mut self, body: Vec<Stmt>) -> StatementReturn {
+        let outer_block: usize = self.block;
+        self.blocks += 1;
+        self.block = self.blocks;
+
+        let mut returned: bool = false;
+        let mut warned: bool = false;
+
+        for statement in body {
+            if returned && !warned {
+                let loc: (usize, usize) = Self::get_loc(&statement.span);
+                self.diagnostics
+                    .push(Diagnostic::warning(&WarningType::UnreachableCode, loc));
+                warned = true;
+            }
+            returned |= Self::leaves_block(&statement);
+            self.statement(statement, false)?;
+        }
+
+        self.block = outer_block;
+        Ok(())
+    }
+
+    /// Checks whether the statement always leaves the block it is in, so that the statements
+    /// after it can't run.
+    const fn leaves_block(statement: &Stmt) -> bool {
+        matches!(
+            statement.node,
+            Statement::Return(_) | Statement::Break | Statement::Continue | Statement::Throw(_)
+        )
+    }
+
+    /// Opens a nested scope for a block whose declarations must not be visible after it.
+    fn enter_scope(&mut self) {
+        let outer: Scope = std::mem::replace(&mut self.scope, Scope::new(None));
+        self.scope = Scope::new(Some(Box::new(outer)));
+    }
+
+    /// Closes the scope opened by [`Self::enter_scope`], warning about the variables declared in
+    /// it that were never read.
+    fn leave_scope(&mut self) {
+        let inner: Scope = std::mem::replace(&mut self.scope, Scope::new(None));
+        self.diagnostics.extend(Self::unread_warnings(&inner));
+        self.diagnostics.extend(Self::unread_store_warnings(&inner));
+        self.scope = *inner.parent.expect("Opened by enter_scope");
+    }
+
+    /// Analyzes the body of a loop, which may run any number of times.
+    fn loop_body(&mut self, body: Vec<Stmt>) -> StatementReturn {
+        let outer_in_loop: bool = self.in_loop;
+        self.in_loop = true;
+        self.block(body)?;
+        self.in_loop = outer_in_loop;
+        Ok(())
+    }
+
+    /// Records a store to the local variable `name`, remembering an unread value stored earlier
+    /// in the same block that it overwrites.
+    fn record_store(&self, name: &str, loc: (usize, usize)) {
+        let Some(usage) = self.scope.usage(name) else {
+            return;
+        };
+
+        let previous: Option<Store> = usage.replace_store(Store {
+            loc,
+            block: self.block,
+            in_loop: self.in_loop,
+        });
+
+        if let Some(previous) = previous
+            && previous.block == self.block
+        {
+            usage.add_overwritten(previous.loc);
+        }
+    }
+
+    /// Creates warnings for the values stored to variables in `scope` that were overwritten or
+    /// never read. A last store in a loop body is left out since the next iteration may still
+    /// read it, and so are variables that are never read at all, which are already reported as
+    /// unused.
+    fn unread_store_warnings(scope: &Scope) -> Vec<Diagnostic> {
+        let mut stores: Vec<(&String, (usize, usize))> = Vec::new();
+        for (name, usage) in scope.usages.iter().filter(|(_, usage)| usage.is_read()) {
+            stores.extend(usage.overwritten().into_iter().map(|loc| (name, loc)));
+            if let Some(store) = usage.unread_store()
+                && !store.in_loop
+            {
+                stores.push((name, store.loc));
+            }
+        }
+        stores.sort_by_key(|(_, loc)| *loc);
+
+        stores
+            .into_iter()
+            .map(|(name, loc)| Diagnostic::warning(&WarningType::DeadStore(name.clone()), loc))
+            .collect()
+    }
+
+    /// Creates warnings for the variables and functions declared in `scope` that were never read.
+    fn unread_warnings(scope: &Scope) -> Vec<Diagnostic> {
+        scope
+            .unread()
+            .into_iter()
+            // `self` is passed to every instance method, whether the method needs it or not
+            .filter(|(name, _)| *name != "self")
+            .map(|(name, usage)| {
+                let warning_type: WarningType = if scope.functions.contains_key(name) {
+                    WarningType::UnusedFunction(name.clone())
+                } else {
+                    WarningType::UnusedVariable(name.clone())
+                };
+                Diagnostic::warning(&warning_type, usage.loc)
+            })
+            .collect()
+    }
+
     #[must_use]
     const fn get_loc(span: &Span) -> (usize, usize) {
         (span.start.0, span.start.1)
     }
 
+    /// Converts a type name from the AST into a type, resolving `Self` to the enclosing class.
+    fn resolve_type(&self, type_name: &str, loc: (usize, usize)) -> ExpressionReturn {
+        match Type::from(type_name) {
+            Type::SelfType => self.class.clone().ok_or_else(|| SemanticError {
+                error_type: SemanticErrorType::SelfOutsideClass(String::from("Self")),
+                line: loc.0,
+                column: loc.1,
+            }),
+            resolved => Ok(resolved),
+        }
+    }
+
     fn variable_declaration(
         &mut self,
         var_type: &str,
@@ -139,7 +423,11 @@ impl SemanticAnalyzer {
         value: Option<Expr>,
         loc: (usize, usize),
     ) -> StatementReturn {
-        let var_type: Type = Type::from(var_type);
+        if var_type.is_empty() {
+            return self.inferred_variable_declaration(name, value, loc);
+        }
+
+        let var_type: Type = self.resolve_type(var_type, loc)?;
 
         if var_type == Type::Void {
             return Err(SemanticError {
@@ -154,11 +442,104 @@ impl SemanticAnalyzer {
         if let Some(value) = value {
             let value_type: Type = self.expression(value)?;
             self.scope.assign_variable(name, &value_type, loc)?;
+            self.record_store(name, loc);
         }
 
         Ok(())
     }
 
+    /// Analyzes a variable declaration without a type, like `let x = 5;`, which takes the type of
+    /// its value.
+    fn inferred_variable_declaration(
+        &mut self,
+        name: &str,
+        value: Option<Expr>,
+        loc: (usize, usize),
+    ) -> StatementReturn {
+        let Some(value) = value else {
+            return Err(SemanticError {
+                error_type: SemanticErrorType::MissingTypeAnnotation(name.to_string()),
+                line: loc.0,
+                column: loc.1,
+            });
+        };
+
+        let value_type: Type = self.expression(value)?;
+        self.scope
+            .add_variable(name.to_string(), value_type.clone(), loc)?;
+        self.scope.assign_variable(name, &value_type, loc)?;
+        self.record_store(name, loc);
+
+        Ok(())
+    }
+
+    fn const_declaration(
+        &mut self,
+        const_type: &str,
+        name: &str,
+        value: Expr,
+        loc: (usize, usize),
+    ) -> StatementReturn {
+        let const_type: Type = self.resolve_type(const_type, loc)?;
+
+        if const_type == Type::Void {
+            return Err(SemanticError {
+                error_type: SemanticErrorType::IllegalVoidVariable(name.to_string()),
+                line: loc.0,
+                column: loc.1,
+            });
+        }
+
+        self.check_constant(name, &value, loc)?;
+
+        let value_type: Type = self.expression(value)?;
+        if value_type != const_type {
+            return Err(SemanticError {
+                error_type: SemanticErrorType::VariableAssignmentTypeMismatch {
+                    expected: (&const_type).into(),
+                    found: (&value_type).into(),
+                },
+                line: loc.0,
+                column: loc.1,
+            });
+        }
+
+        self.scope.add_constant(name.to_string(), const_type, loc)
+    }
+
+    fn check_constant(&self, name: &str, value: &Expr, loc: (usize, usize)) -> StatementReturn {
+        if self.is_constant_expression(value) {
+            Ok(())
+        } else {
+            Err(SemanticError {
+                error_type: SemanticErrorType::NonConstantValue(name.to_string()),
+                line: loc.0,
+                column: loc.1,
+            })
+        }
+    }
+
+    /// Literals, operators applied to constants, constant variables and constant static fields
+    /// are compile-time constants.
+    fn is_constant_expression(&self, expr: &Expr) -> bool {
+        match &expr.node {
+            Expression::Literal(_) => true,
+            Expression::Unary { operand, .. } => self.is_constant_expression(operand),
+            Expression::Binary { left, right, .. } => {
+                self.is_constant_expression(left) && self.is_constant_expression(right)
+            }
+            Expression::Identifier(name) => self.scope.is_constant(name),
+            Expression::MemberAccess { object, member } => match &object.node {
+                Expression::Identifier(class) => self
+                    .scope
+                    .get_class_field(class, member, (0, 0))
+                    .is_ok_and(|field| field.is_const),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
     fn assignment(&mut self, assignee: Expr, value: Expr) -> StatementReturn {
         let aloc: (usize, usize) = Self::get_loc(&assignee.span);
 
@@ -166,7 +547,11 @@ impl SemanticAnalyzer {
         let value_type: Type = self.expression(value)?;
 
         match lvalue {
-            LValue::Variable(name) => self.scope.assign_variable(&name, &value_type, aloc),
+            LValue::Variable(name) => {
+                self.scope.assign_variable(&name, &value_type, aloc)?;
+                self.record_store(&name, aloc);
+                Ok(())
+            }
             LValue::Field { base, field_name } => {
                 let class: Class = self.scope.get_class(&(String::from(&base)), aloc)?;
                 self.scope
@@ -189,11 +574,16 @@ impl SemanticAnalyzer {
 
         match expr.node {
             Expression::Identifier(name) => {
-                self.scope.get_local_variable(&name, loc)?;
+                self.scope.get_declared_variable(&name, loc)?;
                 Ok(LValue::Variable(name))
             }
             Expression::MemberAccess { object, member } => {
-                let expr_type: Type = self.expression(object.as_ref().clone())?;
+                let expr_type: Type = match &object.node {
+                    Expression::Identifier(ident) if self.scope.get_class(ident, loc).is_ok() => {
+                        Type::Class(ident.clone())
+                    }
+                    _ => self.expression(object.as_ref().clone())?,
+                };
                 let field: Field =
                     self.scope
                         .get_class_field(&String::from(&expr_type), &member, loc)?;
@@ -216,6 +606,11 @@ impl SemanticAnalyzer {
                     })
                 }
             }
+            Expression::Index { .. } => Err(SemanticError {
+                error_type: SemanticErrorType::UnsupportedFeature("arrays".into()),
+                line: loc.0,
+                column: loc.1,
+            }),
             e => Err(SemanticError {
                 error_type: SemanticErrorType::InvalidAssignmentTarget(e.name().to_string()),
                 line: loc.0,
@@ -228,7 +623,7 @@ impl SemanticAnalyzer {
         &mut self,
         return_type: &str,
         name: &str,
-        parameters: Vec<(String, String)>,
+        parameters: Vec<Parameter>,
         body: Vec<Stmt>,
         allowed: bool,
         loc: (usize, usize),
@@ -245,41 +640,40 @@ impl SemanticAnalyzer {
             unreachable!("Nested functions are illegal and should have been caught by the parser");
         }
 
-        let return_type: Type = Type::from(return_type);
+        // The signature has already been declared while hoisting
+        let return_type: Type = self.resolve_type(return_type, loc)?;
 
         let mut function_analyzer: Self = Self {
             scope: Scope::new(Some(Box::new(self.scope.clone()))),
             function_return: Some(return_type.clone()),
             found_return: false,
             class: None,
+            diagnostics: Vec::new(),
+            block: 0,
+            blocks: 0,
+            in_loop: false,
+            numeric_coercion: self.numeric_coercion,
         };
 
-        let mut param_types: Vec<Type> = Vec::new();
-
-        for (param_type, param_name) in parameters {
-            let param_type: Type = Type::from(&param_type);
+        for parameter in parameters {
+            let param_loc: (usize, usize) = Self::get_loc(&parameter.span);
+            let param_type: Type = self.resolve_type(&parameter.type_, param_loc)?;
+            function_analyzer.scope.add_variable(
+                parameter.name.clone(),
+                param_type.clone(),
+                param_loc,
+            )?;
             function_analyzer
                 .scope
-                .add_variable(param_name.clone(), param_type.clone(), loc)?;
-            function_analyzer
-                .scope
-                .assign_variable(&param_name, &param_type, loc)?;
-            param_types.push(param_type);
+                .assign_variable(&parameter.name, &param_type, param_loc)?;
         }
 
-        self.scope.add_function(
-            name.to_string(),
-            Function {
-                parameters: param_types,
-                return_type: return_type.clone(),
-                is_static: false,
-            },
-            loc,
-        )?;
-
-        for statement in body {
-            function_analyzer.statement(statement, false)?;
-        }
+        function_analyzer.block(body)?;
+        self.diagnostics.append(&mut function_analyzer.diagnostics);
+        self.diagnostics
+            .extend(Self::unread_warnings(&function_analyzer.scope));
+        self.diagnostics
+            .extend(Self::unread_store_warnings(&function_analyzer.scope));
 
         if return_type != Type::Void && !function_analyzer.found_return {
             return Err(SemanticError {
@@ -292,9 +686,40 @@ impl SemanticAnalyzer {
         Ok(())
     }
 
+    /// Declares a function with the given signature in the current scope.
+    fn function_signature(
+        &mut self,
+        return_type: &str,
+        name: &str,
+        parameters: &[Parameter],
+        loc: (usize, usize),
+    ) -> StatementReturn {
+        let return_type: Type = self.resolve_type(return_type, loc)?;
+
+        let mut param_types: Vec<Type> = Vec::new();
+        let mut param_names: Vec<String> = Vec::new();
+
+        for parameter in parameters {
+            param_types.push(self.resolve_type(&parameter.type_, Self::get_loc(&parameter.span))?);
+            param_names.push(parameter.name.clone());
+        }
+
+        self.scope.add_function(
+            name.to_string(),
+            Function {
+                parameters: param_types,
+                parameter_names: param_names,
+                return_type,
+                is_static: false,
+            },
+            loc,
+        )
+    }
+
     fn class_declaration(
         &mut self,
         name: &str,
+        superclass: Option<&str>,
         body: Vec<Stmt>,
         allowed: bool,
         loc: (usize, usize),
@@ -307,7 +732,28 @@ impl SemanticAnalyzer {
             });
         }
 
-        let mut fields: HashMap<String, Field> = HashMap::new();
+        let bodies: Vec<MethodDeclarationBodyInfo> =
+            self.class_signature(name, superclass, body, loc)?;
+        self.method_bodies(name, bodies)
+    }
+
+    /// Declares a class with its fields and method signatures, returning the method bodies that
+    /// are left to analyze.
+    #[allow(clippy::too_many_lines)]
+    fn class_signature(
+        &mut self,
+        name: &str,
+        superclass: Option<&str>,
+        body: Vec<Stmt>,
+        loc: (usize, usize),
+    ) -> Result<Vec<MethodDeclarationBodyInfo>, SemanticError> {
+        let (mut fields, inherited_methods): (
+            HashMap<String, Field>,
+            HashMap<String, Vec<Function>>,
+        ) = match superclass {
+            Some(superclass) => self.inherited_members(superclass, loc)?,
+            None => (HashMap::new(), HashMap::new()),
+        };
         let mut methods: HashMap<String, Vec<Function>> = HashMap::new();
         let mut body_info: Vec<MethodDeclarationBodyInfo> = Vec::new();
 
@@ -336,22 +782,54 @@ impl SemanticAnalyzer {
 
                     self.field_declaration(
                         &mut fields,
-                        &methods,
+                        &inherited_methods,
                         FieldDeclarationInfo {
                             field_type: type_,
                             name,
                             static_,
+                            const_: false,
                             value,
                         },
                         loc,
                     )?;
                 }
+                Statement::ConstDeclaration { type_, name, value } => {
+                    if found_method {
+                        return Err(SemanticError {
+                            error_type: SemanticErrorType::FieldAfterMethod(name),
+                            line: loc.0,
+                            column: loc.1,
+                        });
+                    }
+
+                    self.check_constant(&name, &value, loc)?;
+                    self.field_declaration(
+                        &mut fields,
+                        &inherited_methods,
+                        FieldDeclarationInfo {
+                            field_type: type_,
+                            name,
+                            static_: true,
+                            const_: true,
+                            value: Some(value),
+                        },
+                        loc,
+                    )?;
+                }
+                Statement::MethodDeclaration { generics, .. } if !generics.is_empty() => {
+                    return Err(SemanticError {
+                        error_type: SemanticErrorType::UnsupportedFeature("generics".into()),
+                        line: loc.0,
+                        column: loc.1,
+                    });
+                }
                 Statement::MethodDeclaration {
                     return_type,
                     name,
                     parameters,
                     body,
                     static_,
+                    ..
                 } => {
                     found_method = true;
                     let ret: MethodDeclarationSignatureReturn = self.method_signature(
@@ -380,16 +858,30 @@ impl SemanticAnalyzer {
             }
         }
 
+        Self::inherit_methods(&mut methods, inherited_methods, loc)?;
+
         self.scope.add_class(
             Class {
                 name: name.to_owned(),
                 fields,
                 methods,
+                superclass: superclass.map(String::from),
             },
             loc,
         )?;
+        self.class = None;
 
-        for info in body_info {
+        Ok(body_info)
+    }
+
+    /// Analyzes the bodies of the methods of the given class.
+    fn method_bodies(
+        &mut self,
+        class_name: &str,
+        bodies: Vec<MethodDeclarationBodyInfo>,
+    ) -> StatementReturn {
+        self.class = Some(Type::Class(class_name.to_string()));
+        for info in bodies {
             self.method_body(info)?;
         }
         self.class = None;
@@ -397,6 +889,65 @@ impl SemanticAnalyzer {
         Ok(())
     }
 
+    /// Returns the fields and methods a class inherits from `superclass`. Constructors are not
+    /// inherited.
+    fn inherited_members(
+        &self,
+        superclass: &str,
+        loc: (usize, usize),
+    ) -> Result<InheritedMembers, SemanticError> {
+        if builtins::get_builtin_types()
+            .iter()
+            .any(|builtin| builtin.name == superclass)
+        {
+            return Err(SemanticError {
+                error_type: SemanticErrorType::IllegalSuperclass(superclass.to_string()),
+                line: loc.0,
+                column: loc.1,
+            });
+        }
+
+        let mut class: Class = self.scope.get_class(superclass, loc)?;
+        class.methods.remove("new");
+
+        Ok((class.fields, class.methods))
+    }
+
+    /// Adds the inherited methods to the methods declared in a class. A declared method with the
+    /// same name and parameter types as an inherited one overrides it, which requires the same
+    /// return type and staticness.
+    fn inherit_methods(
+        methods: &mut HashMap<String, Vec<Function>>,
+        inherited_methods: HashMap<String, Vec<Function>>,
+        loc: (usize, usize),
+    ) -> StatementReturn {
+        for (name, inherited) in inherited_methods {
+            let overloads: &mut Vec<Function> = methods.entry(name.clone()).or_default();
+
+            for method in inherited {
+                match overloads
+                    .iter()
+                    .find(|overload| overload.parameters == method.parameters)
+                {
+                    Some(overload)
+                        if overload.return_type != method.return_type
+                            || overload.is_static != method.is_static =>
+                    {
+                        return Err(SemanticError {
+                            error_type: SemanticErrorType::IllegalOverride(name),
+                            line: loc.0,
+                            column: loc.1,
+                        });
+                    }
+                    Some(_) => {}
+                    None => overloads.push(method),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn field_declaration(
         &self,
         fields: &mut HashMap<String, Field>,
@@ -418,7 +969,7 @@ impl SemanticAnalyzer {
             });
         }
 
-        let field_type: Type = Type::from(&field_info.field_type);
+        let field_type: Type = self.resolve_type(&field_info.field_type, loc)?;
 
         if field_type == Type::Void {
             return Err(SemanticError {
@@ -431,7 +982,7 @@ impl SemanticAnalyzer {
         if let Some(value) = field_info.value {
             let value_type: Type = self.expression(value)?;
 
-            if field_type != value_type {
+            if !self.scope.is_assignable(&value_type, &field_type) {
                 return Err(SemanticError {
                     error_type: SemanticErrorType::FieldInitializationTypeMismatch {
                         expected: (&field_type).into(),
@@ -448,6 +999,7 @@ impl SemanticAnalyzer {
             Field {
                 field_type,
                 is_static: field_info.static_,
+                is_const: field_info.const_,
             },
         );
 
@@ -488,31 +1040,32 @@ impl SemanticAnalyzer {
             constructor = true;
             Type::from(&method_info.class_name)
         } else {
-            Type::from(&method_info.return_type)
+            self.resolve_type(&method_info.return_type, loc)?
         };
 
-        let mut params: Vec<(Type, String)> = Vec::new();
+        let mut params: Vec<(Type, String, (usize, usize))> = Vec::new();
         let mut param_types: Vec<Type> = Vec::new();
+        let mut param_names: Vec<String> = Vec::new();
 
         if !method_info.static_ {
             params.push((
                 Type::Class(method_info.class_name.clone()),
                 "self".to_string(),
+                loc,
             ));
         }
 
-        for (param_type, param_name) in method_info.parameters {
-            let param_type: Type = if param_type == "Self" {
-                Type::Class(method_info.class_name.clone())
-            } else {
-                Type::from(&param_type)
-            };
+        for parameter in method_info.parameters {
+            let param_loc: (usize, usize) = Self::get_loc(&parameter.span);
+            let param_type: Type = self.resolve_type(&parameter.type_, param_loc)?;
             param_types.push(param_type.clone());
-            params.push((param_type, param_name));
+            param_names.push(parameter.name.clone());
+            params.push((param_type, parameter.name, param_loc));
         }
 
         let method: Function = Function {
             parameters: param_types,
+            parameter_names: param_names,
             return_type: return_type.clone(),
             is_static: method_info.static_,
         };
@@ -543,7 +1096,7 @@ impl SemanticAnalyzer {
         ))
     }
 
-    fn method_body(&self, mut method_info: MethodDeclarationBodyInfo) -> StatementReturn {
+    fn method_body(&mut self, mut method_info: MethodDeclarationBodyInfo) -> StatementReturn {
         let mut method_analyzer: Self = Self {
             scope: Scope::new(Some(Box::new(self.scope.clone()))),
             function_return: Some(if method_info.constructor {
@@ -554,20 +1107,28 @@ impl SemanticAnalyzer {
             }),
             found_return: false,
             class: self.class.clone(),
+            diagnostics: Vec::new(),
+            block: 0,
+            blocks: 0,
+            in_loop: false,
+            numeric_coercion: self.numeric_coercion,
         };
 
-        for (ptype, pname) in method_info.parameters {
+        for (ptype, pname, ploc) in method_info.parameters {
             method_analyzer
                 .scope
-                .add_variable(pname.clone(), ptype.clone(), method_info.loc)?;
+                .add_variable(pname.clone(), ptype.clone(), ploc)?;
             method_analyzer
                 .scope
-                .assign_variable(&pname, &ptype, method_info.loc)?;
+                .assign_variable(&pname, &ptype, ploc)?;
         }
 
-        for statement in method_info.body {
-            method_analyzer.statement(statement, false)?;
-        }
+        method_analyzer.block(method_info.body)?;
+        self.diagnostics.append(&mut method_analyzer.diagnostics);
+        self.diagnostics
+            .extend(Self::unread_warnings(&method_analyzer.scope));
+        self.diagnostics
+            .extend(Self::unread_store_warnings(&method_analyzer.scope));
 
         if method_info.return_type != Type::Void && !method_analyzer.found_return {
             return Err(SemanticError {
@@ -580,12 +1141,17 @@ impl SemanticAnalyzer {
         Ok(())
     }
 
+    /// Analyzes an if statement. A variable is only initialized after the statement if every
+    /// branch that doesn't return initializes it, which requires an else branch.
     fn if_statement(
         &mut self,
         conditional_branches: Vec<(Expr, Vec<Stmt>)>,
         else_branch: Option<Vec<Stmt>>,
         loc: (usize, usize),
     ) -> StatementReturn {
+        let before: HashSet<String> = self.scope.initialized_variables();
+        let mut after: Option<HashSet<String>> = None;
+
         for (condition, body) in conditional_branches {
             let condition_type: Type = self.expression(condition)?;
 
@@ -597,20 +1163,53 @@ impl SemanticAnalyzer {
                 });
             }
 
-            for statement in body {
-                self.statement(statement, false)?;
-            }
+            self.branch(body, &before, &mut after)?;
         }
 
         if let Some(else_body) = else_branch {
-            for statement in else_body {
-                self.statement(statement, false)?;
-            }
+            self.branch(else_body, &before, &mut after)?;
+        } else {
+            after = Some(before);
+        }
+
+        if let Some(after) = after {
+            self.scope.set_initialized_variables(&after);
+        }
+
+        Ok(())
+    }
+
+    /// Analyzes one branch of an if statement starting from the variables initialized in
+    /// `before`, and narrows `after` down to the variables initialized at the end of every
+    /// branch analyzed so far. Branches that return don't count, since they don't continue after
+    /// the if statement.
+    fn branch(
+        &mut self,
+        body: Vec<Stmt>,
+        before: &HashSet<String>,
+        after: &mut Option<HashSet<String>>,
+    ) -> StatementReturn {
+        self.scope.set_initialized_variables(before);
+        let returns: bool = body.iter().any(Self::leaves_block);
+        self.block(body)?;
+
+        if !returns {
+            self.join_initialized(after);
         }
 
         Ok(())
     }
 
+    /// Keeps only the variables in `after` that are also initialized at the end of the branch
+    /// that was just analyzed.
+    fn join_initialized(&self, after: &mut Option<HashSet<String>>) {
+        let initialized: HashSet<String> = self.scope.initialized_variables();
+        *after = Some(match after.take() {
+            Some(previous) => previous.intersection(&initialized).cloned().collect(),
+            None => initialized,
+        });
+    }
+
     fn while_statement(
         &mut self,
         condition: Expr,
@@ -627,13 +1226,88 @@ impl SemanticAnalyzer {
             });
         }
 
-        for statement in body {
-            self.statement(statement, false)?;
+        // The body may not run at all, so it doesn't initialize anything for the code after it
+        let before: HashSet<String> = self.scope.initialized_variables();
+        self.loop_body(body)?;
+        self.scope.set_initialized_variables(&before);
+
+        Ok(())
+    }
+
+    fn do_while_statement(&mut self, body: Vec<Stmt>, condition: Expr) -> StatementReturn {
+        // The condition is outside the body, so it can't see the variables declared in it
+        self.enter_scope();
+        self.loop_body(body)?;
+        self.leave_scope();
+
+        let loc: (usize, usize) = Self::get_loc(&condition.span);
+        let condition_type: Type = self.expression(condition)?;
+
+        if condition_type != Type::Boolean {
+            return Err(SemanticError {
+                error_type: SemanticErrorType::NonBooleanCondition((&condition_type).into()),
+                line: loc.0,
+                column: loc.1,
+            });
         }
 
         Ok(())
     }
 
+    /// Analyzes a try/catch statement. The guarded block may fail at any point, so the handler
+    /// starts from the variables initialized before the statement, and a variable is only
+    /// initialized afterwards if both blocks initialize it.
+    fn try_statement(
+        &mut self,
+        body: Vec<Stmt>,
+        error_name: &str,
+        handler: Vec<Stmt>,
+        loc: (usize, usize),
+    ) -> StatementReturn {
+        let before: HashSet<String> = self.scope.initialized_variables();
+        let mut after: Option<HashSet<String>> = None;
+
+        self.branch(body, &before, &mut after)?;
+
+        // The error variable only exists inside the handler. The syntax requires a name for it,
+        // so leaving it unused is not a mistake.
+        self.scope.set_initialized_variables(&before);
+        let returns: bool = handler.iter().any(Self::leaves_block);
+        self.enter_scope();
+        self.scope
+            .add_variable(error_name.to_string(), Type::String, loc)?;
+        self.scope.assign_variable(error_name, &Type::String, loc)?;
+        self.scope.mark_read(error_name);
+        self.block(handler)?;
+        self.leave_scope();
+
+        if !returns {
+            self.join_initialized(&mut after);
+        }
+
+        if let Some(after) = after {
+            self.scope.set_initialized_variables(&after);
+        }
+
+        Ok(())
+    }
+
+    /// Analyzes a throw statement. Like a return, it counts as leaving the function.
+    fn throw_statement(&mut self, message: Expr, loc: (usize, usize)) -> StatementReturn {
+        let message_type: Type = self.expression(message)?;
+
+        if message_type != Type::String {
+            return Err(SemanticError {
+                error_type: SemanticErrorType::NonStringThrow((&message_type).into()),
+                line: loc.0,
+                column: loc.1,
+            });
+        }
+
+        self.found_return = true;
+        Ok(())
+    }
+
     fn return_statement(&mut self, expr: Option<Expr>, loc: (usize, usize)) -> StatementReturn {
         let function_return: Type = match &self.function_return {
             Some(ret) => ret.clone(),
@@ -650,10 +1324,15 @@ impl SemanticAnalyzer {
         let void_return: bool = function_return == Type::Void;
 
         if has_expr == void_return {
+            let found: Type = match expr {
+                Some(expr) => self.expression(expr)?,
+                None => Type::Void,
+            };
+
             Err(SemanticError {
                 error_type: SemanticErrorType::ReturnTypeMismatch {
                     expected: (&function_return).into(),
-                    found: (&self.expression(expr.expect("Checked before"))?).into(),
+                    found: (&found).into(),
                 },
                 line: loc.0,
                 column: loc.1,
@@ -662,7 +1341,7 @@ impl SemanticAnalyzer {
             let expr: Expr = expr.expect("Checked before");
             let expr_type: Type = self.expression(expr)?;
 
-            if expr_type == function_return {
+            if self.scope.is_assignable(&expr_type, &function_return) {
                 self.found_return = true;
                 Ok(())
             } else {
@@ -685,7 +1364,7 @@ impl SemanticAnalyzer {
         let loc: (usize, usize) = Self::get_loc(&expr.span);
 
         match expr.node {
-            Expression::Literal(literal) => Ok(Self::literal(&literal)),
+            Expression::Literal(literal) => Self::literal(&literal, loc),
             Expression::Identifier(identifier) => self.scope.get_variable(&identifier, loc),
             Expression::Binary {
                 left,
@@ -693,24 +1372,70 @@ impl SemanticAnalyzer {
                 right,
             } => self.binary(*left, &operator, *right),
             Expression::Unary { operator, operand } => self.unary(&operator, *operand),
-            Expression::Call { callee, arguments } => self.call(*callee, arguments),
+            Expression::Call {
+                callee,
+                arguments,
+                named_arguments,
+            } => {
+                let name: String = match &callee.node {
+                    Expression::Identifier(name)
+                    | Expression::MemberAccess { member: name, .. } => name.clone(),
+                    _ => String::new(),
+                };
+                let return_type: Type = self.call(*callee, arguments, named_arguments)?;
+
+                if return_type == Type::Void {
+                    return Err(SemanticError {
+                        error_type: SemanticErrorType::VoidValue(name),
+                        line: loc.0,
+                        column: loc.1,
+                    });
+                }
+
+                Ok(return_type)
+            }
             Expression::MemberAccess { object, member } => {
                 self.member_access(*object, &member, loc)
             }
-            Expression::Self_ => self
-                .class
-                .as_ref()
-                .ok_or_else(|| unreachable!("Should be caught by parser"))
-                .cloned(),
+            Expression::Self_ => self.class.clone().ok_or_else(|| SemanticError {
+                error_type: SemanticErrorType::SelfOutsideClass(String::from("self")),
+                line: loc.0,
+                column: loc.1,
+            }),
+            Expression::ArrayLiteral(_) | Expression::Index { .. } => Err(SemanticError {
+                error_type: SemanticErrorType::UnsupportedFeature("arrays".into()),
+                line: loc.0,
+                column: loc.1,
+            }),
+            Expression::Tuple(_) => Err(SemanticError {
+                error_type: SemanticErrorType::UnsupportedFeature("tuples".into()),
+                line: loc.0,
+                column: loc.1,
+            }),
+            Expression::MapLiteral(_) => Err(SemanticError {
+                error_type: SemanticErrorType::UnsupportedFeature("maps".into()),
+                line: loc.0,
+                column: loc.1,
+            }),
+            Expression::Lambda { .. } => Err(SemanticError {
+                error_type: SemanticErrorType::UnsupportedFeature("lambda expressions".into()),
+                line: loc.0,
+                column: loc.1,
+            }),
         }
     }
 
-    const fn literal(literal: &Literal) -> Type {
+    fn literal(literal: &Literal, loc: (usize, usize)) -> ExpressionReturn {
         match literal {
-            Literal::Integer(_) => Type::Int,
-            Literal::Float(_) => Type::Float,
-            Literal::Boolean(_) => Type::Boolean,
-            Literal::String(_) => Type::String,
+            Literal::Integer(_) => Ok(Type::Int),
+            Literal::Float(_) => Ok(Type::Float),
+            Literal::Boolean(_) => Ok(Type::Boolean),
+            Literal::String(_) => Ok(Type::String),
+            Literal::Null => Err(SemanticError {
+                error_type: SemanticErrorType::UnsupportedFeature("null".into()),
+                line: loc.0,
+                column: loc.1,
+            }),
         }
     }
 
@@ -718,26 +1443,54 @@ impl SemanticAnalyzer {
         let lloc: (usize, usize) = Self::get_loc(&left.span);
         let rloc: (usize, usize) = Self::get_loc(&right.span);
 
+        if matches!(operator, BinaryOperator::Divide | BinaryOperator::Modulo)
+            && constants::evaluate(&right) == Some(ConstantValue::Int(0))
+        {
+            return Err(SemanticError {
+                error_type: SemanticErrorType::DivisionByZero,
+                line: rloc.0,
+                column: rloc.1,
+            });
+        }
+
         let ltype: Type = self.expression(left)?;
         let rtype: Type = self.expression(right)?;
 
-        let op_name: &'static str = match operator {
-            BinaryOperator::Add => "Add",
-            BinaryOperator::Subtract => "Sub",
-            BinaryOperator::Multiply => "Mul",
-            BinaryOperator::Divide => "Div",
-            BinaryOperator::Equals => "Eq",
-            BinaryOperator::NotEquals => "Ne",
-            BinaryOperator::LessThan => "Lt",
-            BinaryOperator::GreaterThan => "Gt",
-            BinaryOperator::LessThanOrEqual => "Le",
-            BinaryOperator::GreaterThanOrEqual => "Ge",
-            BinaryOperator::And => "And",
-            BinaryOperator::Or => "Or",
-        };
+        if self.numeric_coercion == NumericCoercion::Strict
+            && matches!(
+                (&ltype, &rtype),
+                (Type::Int, Type::Float) | (Type::Float, Type::Int)
+            )
+        {
+            return Err(SemanticError {
+                error_type: SemanticErrorType::ImplicitNumericConversion {
+                    left: (&ltype).into(),
+                    right: (&rtype).into(),
+                },
+                line: lloc.0,
+                column: lloc.1,
+            });
+        }
+
+        // Logical operators short-circuit, so they can't be implemented by methods
+        if matches!(operator, BinaryOperator::And | BinaryOperator::Or) {
+            return match (&ltype, &rtype) {
+                (Type::Boolean, Type::Boolean) => Ok(Type::Boolean),
+                (Type::Boolean, _) => Err(SemanticError {
+                    error_type: SemanticErrorType::NonBooleanOperand((&rtype).into()),
+                    line: rloc.0,
+                    column: rloc.1,
+                }),
+                _ => Err(SemanticError {
+                    error_type: SemanticErrorType::NonBooleanOperand((&ltype).into()),
+                    line: lloc.0,
+                    column: lloc.1,
+                }),
+            };
+        }
 
-        let lhs_func_name: String = format!("_bop{op_name}");
-        let rhs_func_name: String = format!("_bopR{op_name}");
+        let lhs_func_name: String = operator.method_name();
+        let rhs_func_name: String = operator.reflected_method_name();
 
         let lclass: Class = self.scope.get_class(&String::from(&ltype), lloc)?;
         let rclass: Class = self.scope.get_class(&String::from(&rtype), rloc)?;
@@ -757,12 +1510,7 @@ impl SemanticAnalyzer {
 
         let op_type: Type = self.expression(operand)?;
 
-        let func_name: String = format!(
-            "_uop{}",
-            match operator {
-                UnaryOperator::Not => "Not",
-            }
-        );
+        let func_name: String = operator.method_name();
 
         let op_class: Class = self.scope.get_class(&String::from(&op_type), loc)?;
 
@@ -772,19 +1520,45 @@ impl SemanticAnalyzer {
             .clone())
     }
 
-    fn call(&self, callee: Expr, arguments: Vec<Expr>) -> ExpressionReturn {
+    fn call(
+        &self,
+        callee: Expr,
+        arguments: Vec<Expr>,
+        named_arguments: Vec<(String, Expr)>,
+    ) -> ExpressionReturn {
         let arguments: Vec<Type> = arguments
             .into_iter()
             .map(|arg| self.expression(arg))
             .collect::<Result<_, _>>()?;
+        let named_arguments: Vec<(String, Type)> = named_arguments
+            .into_iter()
+            .map(|(name, arg)| Ok((name, self.expression(arg)?)))
+            .collect::<Result<_, _>>()?;
 
         let loc: (usize, usize) = Self::get_loc(&callee.span);
 
         Ok(match callee.node {
             Expression::Identifier(name) => {
                 let func: Function = self.scope.get_function(&name, loc)?;
-                if func.parameters == arguments {
+                let arguments: Vec<Type> = func
+                    .arrange_arguments(&name, &arguments, &named_arguments)
+                    .map_err(|error_type| SemanticError {
+                        error_type,
+                        line: loc.0,
+                        column: loc.1,
+                    })?;
+                if self.scope.accepts(&func.parameters, &arguments) {
                     func.return_type
+                } else if func.parameters.len() != arguments.len() {
+                    return Err(SemanticError {
+                        error_type: SemanticErrorType::ArgumentCountMismatch {
+                            func: name,
+                            expected: func.parameters.len(),
+                            found: arguments.len(),
+                        },
+                        line: loc.0,
+                        column: loc.1,
+                    });
                 } else {
                     return Err(SemanticError {
                         error_type: SemanticErrorType::ArgumentTypeMismatch {
@@ -810,11 +1584,19 @@ impl SemanticAnalyzer {
                 };
                 let class: Class = self.scope.get_class(&String::from(&object_type), loc)?;
                 class
-                    .get_method(&member, &arguments, loc)?
+                    .resolve_method(&self.scope, &member, &arguments, &named_arguments, loc)?
                     .clone()
                     .return_type
             }
-            _ => unreachable!("Parser only allows identifiers and member accesses as callees."),
+            _ => {
+                return Err(SemanticError {
+                    error_type: SemanticErrorType::UnsupportedFeature(
+                        "calling expressions other than functions and methods".into(),
+                    ),
+                    line: loc.0,
+                    column: loc.1,
+                });
+            }
         })
     }
 
@@ -846,3 +1628,627 @@ impl SemanticAnalyzer {
         }
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod semantics_tests {
+    use super::*;
+    use crate::{
+        builtins::get_builtin_types,
+        constants::{evaluate, fold},
+        errors::Severity,
+        lints::LintLevel,
+        resolve::{Symbol, SymbolKind, resolve},
+    };
+    use lexer::Lexer;
+    use parser::{Parser, types::Program};
+
+    fn parse(source: &str) -> Program {
+        Parser::parse(Lexer::tokenize(source).unwrap()).unwrap()
+    }
+
+    /// Parses the given source as the value of a variable declaration.
+    fn expression(source: &str) -> Expr {
+        let program: Program = parse(&format!("void f() {{ let v = {source}; }}"));
+        let Statement::FunctionDeclaration { body, .. } = &program.statements[0].node else {
+            unreachable!()
+        };
+        let Statement::VariableDeclaration { value, .. } = &body[0].node else {
+            unreachable!()
+        };
+        value.clone().unwrap()
+    }
+
+    #[test]
+    fn constant_equality_of_mixed_numbers() {
+        // Mixed operands are compared by the runtime after converting the int, so they are left
+        // unfolded instead of being reported as unequal
+        assert_eq!(evaluate(&expression("1 == 1.0")), None);
+        assert_eq!(evaluate(&expression("1 != 1.0")), None);
+        assert_eq!(evaluate(&expression("1 != 2.0")), None);
+
+        assert_eq!(
+            evaluate(&expression("1 != 1")),
+            Some(ConstantValue::Boolean(false))
+        );
+        assert_eq!(
+            evaluate(&expression("1.5 != 2.5")),
+            Some(ConstantValue::Boolean(true))
+        );
+        assert_eq!(
+            evaluate(&expression("\"a\" == \"a\"")),
+            Some(ConstantValue::Boolean(true))
+        );
+    }
+
+    /// Folds the given function body and returns the folded function as source code.
+    fn folded(body: &str) -> String {
+        let mut program: Program = parse(&format!("void f(int x) {{ {body} }}"));
+        fold(&mut program);
+        program.to_source()
+    }
+
+    #[test]
+    fn fold_arithmetic() {
+        assert_eq!(
+            folded("let a = 2 * 3 + x; let b = x + 2 ** 3 % 5; let c = -(2 - 5);"),
+            "void f(int x) {\n    let a = 6 + x;\n    let b = x + 3;\n    let c = 3;\n}\n"
+        );
+        assert_eq!(
+            folded("let a = 1.5 * 4.0; let s = \"ab\" + \"cd\"; let t = \"ab\" * 3;"),
+            "void f(int x) {\n    let a = 6.0;\n    let s = \"abcd\";\n    let t = \"ababab\";\n}\n"
+        );
+    }
+
+    #[test]
+    fn fold_comparisons() {
+        assert_eq!(
+            folded("let a = 3 < 4 && !false; let b = 2.5 >= 3.0; let c = \"a\" != \"b\";"),
+            "void f(int x) {\n    let a = true;\n    let b = false;\n    let c = true;\n}\n"
+        );
+    }
+
+    #[test]
+    fn fold_keeps_runtime_behavior() {
+        // Each of these fails, overflows or has no literal at runtime, or is too large to inline
+        for body in [
+            "let a = 7 / 0;",
+            "let a = 7 % 0;",
+            "let a = 2 ** -1;",
+            "let a = 2147483647 + 1;",
+            "let a = -2147483647 - 2;",
+            "let a = 65536 * 65536;",
+            "let a = 1.0 / 0.0;",
+            "let a = 1 == 1.0;",
+            "let a = \"ab\" * 1500000000;",
+            "let a = \"ab\" * -1;",
+            "let a = x * 2;",
+        ] {
+            let source: String = format!("void f(int x) {{ {body} }}");
+            assert_eq!(folded(body), parse(&source).to_source(), "{body}");
+        }
+    }
+
+    #[test]
+    fn fold_at_integer_limits() {
+        assert_eq!(
+            folded("let a = 2147483646 + 1; let b = -2147483647 - 1;"),
+            "void f(int x) {\n    let a = 2147483647;\n    let b = -2147483648;\n}\n"
+        );
+    }
+
+    /// Returns the kind and declaration site of the symbol the identifier at the given column of
+    /// the first line refers to.
+    fn resolved(source: &str, column: usize) -> Option<(String, SymbolKind, (usize, usize))> {
+        resolve(&parse(source))
+            .reference_at((1, column))
+            .map(|symbol: &Symbol| (symbol.name.clone(), symbol.kind, symbol.declared_at))
+    }
+
+    #[test]
+    fn resolve_shadowed_variables() {
+        let source: &str = "void f() { int a = 1; int b = a; int a = b; int c = a; }";
+
+        assert_eq!(
+            resolved(source, 31),
+            Some((String::from("a"), SymbolKind::Variable, (1, 12)))
+        );
+        assert_eq!(
+            resolved(source, 42),
+            Some((String::from("b"), SymbolKind::Variable, (1, 23)))
+        );
+        assert_eq!(
+            resolved(source, 53),
+            Some((String::from("a"), SymbolKind::Variable, (1, 34)))
+        );
+    }
+
+    #[test]
+    fn resolve_parameters() {
+        let source: &str = "void f(int a) { let g = |int a| a; int b = a; }";
+
+        // The lambda's parameter shadows the function's only inside the lambda
+        assert_eq!(
+            resolved(source, 33),
+            Some((String::from("a"), SymbolKind::Parameter, (1, 26)))
+        );
+        assert_eq!(
+            resolved(source, 44),
+            Some((String::from("a"), SymbolKind::Parameter, (1, 8)))
+        );
+        assert_eq!(resolved("void f() { int b = a; }", 20), None);
+    }
+
+    #[test]
+    fn resolve_class_members() {
+        let source: &str = "int helper() { return 1; } class Point { int x; \
+            int get() { return helper() + self.x; } static Point make() { return Point.new(); } }";
+
+        assert_eq!(
+            resolved(source, 68),
+            Some((String::from("helper"), SymbolKind::Function, (1, 1)))
+        );
+        assert_eq!(
+            resolved(source, 118),
+            Some((String::from("Point"), SymbolKind::Class, (1, 28)))
+        );
+        // Fields are accessed through `self`, not resolved as identifiers
+        assert_eq!(resolved(source, 84), None);
+    }
+
+    #[test]
+    fn builtin_methods() {
+        let classes: Vec<Class> = get_builtin_types();
+        let class = |name: &str| classes.iter().find(|class| class.name == name).unwrap();
+
+        let print: &Vec<Function> = &class("Builtin").methods["print"];
+        assert_eq!(print.len(), 4);
+        assert!(print.iter().all(|function| function.is_static));
+
+        let to_string: &Vec<Function> = &class("int").methods["toString"];
+        assert_eq!(to_string.len(), 1);
+        assert!(!to_string[0].is_static);
+        assert!(to_string[0].parameters.is_empty());
+        assert_eq!(to_string[0].return_type, Type::String);
+
+        let add: &Vec<Function> = &class("int").methods["_bopAdd"];
+        let overloads: Vec<(&[Type], &Type)> = add
+            .iter()
+            .map(|function| (function.parameters.as_slice(), &function.return_type))
+            .collect();
+        assert_eq!(
+            overloads,
+            [
+                (&[Type::Int][..], &Type::Int),
+                (&[Type::Float][..], &Type::Float)
+            ]
+        );
+    }
+
+    /// Analyzes the given program with the default options.
+    fn analyzed(source: &str) -> Result<Vec<Diagnostic>, SemanticError> {
+        SemanticAnalyzer::analyze(parse(source))
+    }
+
+    #[test]
+    fn null_is_unsupported() {
+        let error: SemanticError = analyzed("void f() { let v = null; }").unwrap_err();
+        assert_eq!(
+            error.error_type,
+            SemanticErrorType::UnsupportedFeature(String::from("null"))
+        );
+        assert_eq!((error.line, error.column), (1, 20));
+    }
+
+    /// Analyzes the given declarations together with an empty entry point.
+    fn checked(source: &str) -> Result<Vec<Diagnostic>, SemanticError> {
+        analyzed(&format!(
+            "{source} class Main {{ static int main() {{ return 0; }} }}"
+        ))
+    }
+
+    /// Returns the names and locations of the warnings reported for the given declarations.
+    fn warnings(source: &str) -> Vec<(&'static str, (usize, usize))> {
+        checked(source)
+            .unwrap()
+            .iter()
+            .map(|diagnostic| (diagnostic.name, (diagnostic.line, diagnostic.column)))
+            .collect()
+    }
+
+    /// Returns the error reported for the given declarations and its location.
+    fn error(source: &str) -> (SemanticErrorType, (usize, usize)) {
+        let error: SemanticError = checked(source).unwrap_err();
+        (error.error_type, (error.line, error.column))
+    }
+
+    #[test]
+    fn lint_unreachable_code() {
+        assert_eq!(
+            warnings("void _f() { while (true) { break; Builtin.println(\"a\"); } }"),
+            vec![("UnreachableCode", (1, 35))]
+        );
+        assert_eq!(
+            warnings("void _f() { if (true) { return; } Builtin.println(\"a\"); }"),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn lint_unused_variables_and_functions() {
+        assert_eq!(
+            warnings("void f(int p) { int x = 1; }"),
+            vec![
+                ("UnusedVariable", (1, 8)),
+                ("UnusedVariable", (1, 17)),
+                ("UnusedFunction", (1, 1))
+            ]
+        );
+
+        // Names starting with '_' opt out, and reads through calls count as uses
+        assert_eq!(
+            warnings(
+                "void _f(int _p) { int _x = 1; } int g(int a) { return a; } \
+                 void _h() { int b = g(1); Builtin.println(b.toString()); }"
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn lint_dead_store() {
+        assert_eq!(
+            warnings("void _f() { int x = 1; x = 2; Builtin.println(x.toString()); }"),
+            vec![("DeadStore", (1, 13))]
+        );
+        assert_eq!(
+            warnings("void _f() { int x = 1; Builtin.println(x.toString()); x = 2; }"),
+            vec![("DeadStore", (1, 55))]
+        );
+
+        // A variable that is never read is only reported as unused
+        assert_eq!(
+            warnings("void _f() { int x = 1; x = 2; }"),
+            vec![("UnusedVariable", (1, 13))]
+        );
+
+        // The first value is still read when the branch is not taken
+        assert_eq!(
+            warnings(
+                "void _f() { int x = 1; if (true) { x = 2; } Builtin.println(x.toString()); }"
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn lint_levels() {
+        let config: LintConfig = LintConfig::from_config(
+            "[project]\nunused_function = \"allow\"\n\n[lints]\n# Fail on unused variables\n\
+             unused_variable = \"deny\"\nunused_function = \"allow\" # Not needed\n",
+        )
+        .unwrap();
+        assert_eq!(config.level("unused_variable"), LintLevel::Deny);
+        assert_eq!(config.level("unused_function"), LintLevel::Allow);
+        assert_eq!(config.level("dead_store"), LintLevel::Warn);
+
+        let options: AnalyzerOptions = AnalyzerOptions {
+            lints: config,
+            ..AnalyzerOptions::default()
+        };
+        let diagnostics: Vec<Diagnostic> = SemanticAnalyzer::analyze_with_options(
+            parse(
+                "void f() { int x = 1; x = 2; Builtin.println(x.toString()); int y = 1; } \
+                 class Main { static int main() { return 0; } }",
+            ),
+            &options,
+        )
+        .unwrap();
+        let reported: Vec<(&str, Severity)> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.name, diagnostic.severity))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                ("UnusedVariable", Severity::Error),
+                ("DeadStore", Severity::Warning)
+            ]
+        );
+
+        assert_eq!(
+            LintConfig::from_config("[lints]\nunused_value = \"deny\""),
+            Err(String::from("Unknown lint 'unused_value' on line 2"))
+        );
+        assert_eq!(
+            LintConfig::from_config("[lints]\ndead_store = \"forbid\""),
+            Err(String::from("Unknown lint level 'forbid' on line 2"))
+        );
+        assert_eq!(
+            LintConfig::from_config("[lints]\ndead_store"),
+            Err(String::from("Expected 'name = \"level\"' on line 2"))
+        );
+    }
+
+    #[test]
+    fn definite_initialization() {
+        for body in [
+            "int x; x = 1;",
+            "int x; if (true) { x = 1; } else { x = 2; }",
+            "int x; if (true) { return; } else { x = 2; }",
+            "int x; do { x = 1; } while (false);",
+        ] {
+            assert_eq!(
+                warnings(&format!(
+                    "void _f() {{ {body} Builtin.println(x.toString()); }}"
+                )),
+                vec![],
+                "{body}"
+            );
+        }
+
+        for (body, column) in [
+            ("int x;", 36),
+            ("int x; if (true) { x = 1; }", 57),
+            ("int x; while (true) { x = 1; }", 60),
+        ] {
+            assert_eq!(
+                error(&format!(
+                    "void _f() {{ {body} Builtin.println(x.toString()); }}"
+                )),
+                (
+                    SemanticErrorType::VariableUninitialized(String::from("x")),
+                    (1, column)
+                ),
+                "{body}"
+            );
+        }
+    }
+
+    #[test]
+    fn call_arity_and_argument_types() {
+        let add: &str = "int add(int a, int b) { return a + b; } void v() { }";
+        assert_eq!(
+            warnings(&format!(
+                "{add} void _f() {{ v(); Builtin.println(add(1, 2).toString()); }}"
+            )),
+            vec![]
+        );
+
+        assert_eq!(
+            error(&format!("{add} void _f() {{ int x = add(1); }}")),
+            (
+                SemanticErrorType::ArgumentCountMismatch {
+                    func: String::from("add"),
+                    expected: 2,
+                    found: 1
+                },
+                (1, 74)
+            )
+        );
+        assert_eq!(
+            error(&format!("{add} void _f() {{ int x = add(1, true); }}")),
+            (
+                SemanticErrorType::ArgumentTypeMismatch {
+                    func: String::from("add"),
+                    expected: vec![String::from("int"), String::from("int")],
+                    found: vec![String::from("int"), String::from("bool")]
+                },
+                (1, 74)
+            )
+        );
+        assert_eq!(
+            error(&format!("{add} void _f() {{ int x = v(); }}")),
+            (SemanticErrorType::VoidValue(String::from("v")), (1, 74))
+        );
+    }
+
+    #[test]
+    fn inheritance() {
+        let animal: &str = "class Animal { int legs; static Self Animal() { self.legs = 4; } \
+                            int count() { return self.legs; } }";
+
+        assert_eq!(
+            warnings(&format!(
+                "{animal} class Dog : Animal {{ static Self Dog() {{ self.legs = 4; }} \
+                 int count() {{ return 2; }} int twice() {{ return self.count() * 2; }} }} \
+                 void _f() {{ Animal a = Dog.new(); Dog d = Dog.new(); \
+                 Builtin.println((a.count() + d.legs + d.twice()).toString()); }}"
+            )),
+            vec![]
+        );
+
+        let dog: &str = "class Dog : Animal { static Self Dog() { self.legs = 4; } }";
+        assert_eq!(
+            warnings(&format!(
+                "{animal} {dog} class Vet {{ static int check(Animal a) {{ return a.legs; }} \
+                 static string check(Dog d) {{ return d.legs.toString(); }} }} \
+                 void pet(Animal a) {{ Builtin.println(a.count().toString()); }} \
+                 void _f() {{ pet(Dog.new()); Builtin.println(Vet.check(Dog.new())); \
+                 Builtin.println(Vet.check(Animal.new()).toString()); }}"
+            )),
+            vec![]
+        );
+        assert_eq!(
+            error(&format!(
+                "{animal} {dog} class Vet {{ static void check(Animal a, Dog d) {{ }} \
+                 static void check(Dog d, Animal a) {{ }} }} \
+                 void _f() {{ Vet.check(Dog.new(), Dog.new()); }}"
+            )),
+            (
+                SemanticErrorType::AmbiguousMethodCall {
+                    class: String::from("Vet"),
+                    method: String::from("check"),
+                    argument_types: vec![String::from("Dog"), String::from("Dog")]
+                },
+                (1, 266)
+            )
+        );
+        assert_eq!(
+            error(&format!(
+                "{animal} {dog} void _f() {{ Dog d = Animal.new(); }}"
+            )),
+            (
+                SemanticErrorType::VariableAssignmentTypeMismatch {
+                    expected: String::from("Dog"),
+                    found: String::from("Animal")
+                },
+                (1, 174)
+            )
+        );
+        assert_eq!(
+            error(&format!(
+                "{animal} class Dog : Animal {{ float count() {{ return 1.0; }} }}"
+            )),
+            (
+                SemanticErrorType::IllegalOverride(String::from("count")),
+                (1, 102)
+            )
+        );
+        assert_eq!(
+            error(&format!("{animal} class Dog : Animal {{ int legs; }}")),
+            (
+                SemanticErrorType::DuplicateField(String::from("legs")),
+                (1, 123)
+            )
+        );
+        assert_eq!(
+            error("class Text : string { }"),
+            (
+                SemanticErrorType::IllegalSuperclass(String::from("string")),
+                (1, 1)
+            )
+        );
+    }
+
+    #[test]
+    fn self_type() {
+        let point: &str = "int x; static Self Point() { self.x = 1; }";
+        assert_eq!(
+            warnings(&format!(
+                "class Point {{ {point} Self moved() {{ return self; }} \
+                 static Self origin() {{ return Point.new(); }} }} \
+                 void _f() {{ Point p = Point.origin(); Builtin.println(p.moved().x.toString()); }}"
+            )),
+            vec![]
+        );
+
+        assert_eq!(
+            error(&format!(
+                "class Point {{ {point} Self moved() {{ return 1; }} }}"
+            )),
+            (
+                SemanticErrorType::ReturnTypeMismatch {
+                    expected: String::from("Point"),
+                    found: String::from("int")
+                },
+                (1, 73)
+            )
+        );
+
+        // The parser already rejects `Self` outside classes, so the AST is built by hand
+        let mut program: Program =
+            parse("int f() { return 1; } class Main { static int main() { return 0; } }");
+        let Statement::FunctionDeclaration { return_type, .. } = &mut program.statements[0].node
+        else {
+            unreachable!()
+        };
+        *return_type = String::from("Self");
+        let error: SemanticError = SemanticAnalyzer::analyze(program).unwrap_err();
+        assert_eq!(
+            error.error_type,
+            SemanticErrorType::SelfOutsideClass(String::from("Self"))
+        );
+    }
+
+    #[test]
+    fn type_inference() {
+        assert_eq!(
+            warnings(
+                "void _f() { let x = 5; let s = \"a\" + x.toString(); let b = x < 6; \
+                 if (b) { Builtin.println(s); } }"
+            ),
+            vec![]
+        );
+
+        assert_eq!(
+            error("void _f() { let x; }"),
+            (
+                SemanticErrorType::MissingTypeAnnotation(String::from("x")),
+                (1, 13)
+            )
+        );
+        assert_eq!(
+            error("void _f() { let x = 5; x = \"a\"; }"),
+            (
+                SemanticErrorType::VariableAssignmentTypeMismatch {
+                    expected: String::from("int"),
+                    found: String::from("string")
+                },
+                (1, 24)
+            )
+        );
+    }
+
+    #[test]
+    fn hoisting() {
+        assert_eq!(
+            warnings(
+                "void _f() { Later l = Later.new(); Builtin.println(isEven(l.x).toString()); } \
+                 bool isEven(int n) { if (n == 0) { return true; } return isOdd(n - 1); } \
+                 bool isOdd(int n) { if (n == 0) { return false; } return isEven(n - 1); } \
+                 class Later { int x; static Self Later() { self.x = 4; } }"
+            ),
+            vec![]
+        );
+
+        assert_eq!(
+            error("void _f() { int x = missing(); }"),
+            (
+                SemanticErrorType::FunctionNotFound(String::from("missing")),
+                (1, 21)
+            )
+        );
+    }
+
+    #[test]
+    fn do_while_condition_scope() {
+        assert_eq!(
+            warnings("void _f() { int i = 0; do { int next = i + 1; i = next; } while (i < 3); }"),
+            vec![]
+        );
+
+        // The body's variables are out of scope in the condition, like in the generated C#
+        assert_eq!(
+            error("void _f() { do { bool again = false; } while (again); }"),
+            (
+                SemanticErrorType::VariableNotFound(String::from("again")),
+                (1, 46)
+            )
+        );
+    }
+
+    #[test]
+    fn catch_variable_scope() {
+        // Unused error variables are not reported, and each handler declares its own
+        assert_eq!(
+            warnings(
+                "void _f() { try { throw \"a\"; } catch (e) { } \
+                 int x; try { x = 1; } catch (e) { Builtin.println(e); x = 2; } \
+                 Builtin.println(x.toString()); }"
+            ),
+            vec![]
+        );
+
+        assert_eq!(
+            error(
+                "void _f() { try { throw \"a\"; } catch (e) { Builtin.println(e); } \
+                 Builtin.println(e); }"
+            ),
+            (
+                SemanticErrorType::VariableNotFound(String::from("e")),
+                (1, 82)
+            )
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Contains the configuration of how the warnings found during semantic analysis are reported.
+
+use std::collections::HashMap;
+
+use crate::errors::{Diagnostic, Severity};
+
+/// The lints that can be configured, by their name in a config and the name of the warning they
+/// control.
+pub const LINTS: [(&str, &str); 4] = [
+    ("unreachable_code", "UnreachableCode"),
+    ("unused_variable", "UnusedVariable"),
+    ("unused_function", "UnusedFunction"),
+    ("dead_store", "DeadStore"),
+];
+
+/// How a lint is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LintLevel {
+    /// The lint is not reported at all.
+    Allow,
+    /// The lint is reported as a warning.
+    #[default]
+    Warn,
+    /// The lint is reported as an error, which fails the compilation.
+    Deny,
+}
+
+impl LintLevel {
+    /// Parses a lint level from its name, which is one of `allow`, `warn` or `deny`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "allow" => Some(Self::Allow),
+            "warn" => Some(Self::Warn),
+            "deny" => Some(Self::Deny),
+            _ => None,
+        }
+    }
+}
+
+/// The levels of the lints reported during semantic analysis. Lints that are not configured are
+/// reported as warnings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintConfig {
+    /// The configured levels, by warning name, e.g. `UnusedVariable`.
+    levels: HashMap<&'static str, LintLevel>,
+}
+
+impl LintConfig {
+    /// Sets the level of the lint with the given name, e.g. `unused_variable`.
+    ///
+    /// # Errors
+    /// When there is no lint with the given name.
+    pub fn set(&mut self, lint: &str, level: LintLevel) -> Result<(), String> {
+        let (_, warning_name) = LINTS
+            .iter()
+            .find(|(name, _)| *name == lint)
+            .ok_or_else(|| format!("Unknown lint '{lint}'"))?;
+
+        self.levels.insert(warning_name, level);
+        Ok(())
+    }
+
+    /// Returns the level of the lint with the given name, e.g. `unused_variable`.
+    #[must_use]
+    pub fn level(&self, lint: &str) -> LintLevel {
+        LINTS
+            .iter()
+            .find(|(name, _)| *name == lint)
+            .and_then(|(_, warning_name)| self.levels.get(warning_name))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Reads the lint levels from the `[lints]` section of a project config, which contains one
+    /// `name = "level"` entry per line, e.g. `unused_variable = "deny"`. Other sections, empty
+    /// lines and comments starting with `#` are ignored.
+    ///
+    /// # Errors
+    /// When an entry in the `[lints]` section is malformed or names an unknown lint or level.
+    pub fn from_config(config: &str) -> Result<Self, String> {
+        let mut lint_config: Self = Self::default();
+        let mut in_section: bool = false;
+
+        for (index, line) in config.lines().enumerate() {
+            let line: &str = line.split('#').next().unwrap_or_default().trim();
+
+            if line.starts_with('[') {
+                in_section = line == "[lints]";
+            }
+            if line.is_empty() || line.starts_with('[') || !in_section {
+                continue;
+            }
+
+            let (name, level) = line
+                .split_once('=')
+                .ok_or_else(|| format!("Expected 'name = \"level\"' on line {}", index + 1))?;
+            let level: &str = level.trim().trim_matches('"');
+            let level: LintLevel = LintLevel::from_name(level)
+                .ok_or_else(|| format!("Unknown lint level '{level}' on line {}", index + 1))?;
+
+            lint_config
+                .set(name.trim(), level)
+                .map_err(|e| format!("{e} on line {}", index + 1))?;
+        }
+
+        Ok(lint_config)
+    }
+
+    /// Applies the configured levels to the given diagnostics, dropping allowed warnings and
+    /// turning denied ones into errors.
+    pub(crate) fn apply(&self, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        diagnostics
+            .into_iter()
+            .filter_map(|mut diagnostic| {
+                if diagnostic.severity != Severity::Warning {
+                    return Some(diagnostic);
+                }
+
+                match self
+                    .levels
+                    .get(diagnostic.name)
+                    .copied()
+                    .unwrap_or_default()
+                {
+                    LintLevel::Allow => None,
+                    LintLevel::Warn => Some(diagnostic),
+                    LintLevel::Deny => {
+                        diagnostic.severity = Severity::Error;
+                        Some(diagnostic)
+                    }
+                }
+            })
+            .collect()
+    }
+}
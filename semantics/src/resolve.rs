@@ -0,0 +1,196 @@
+//! Contains the name resolution pass, which links every identifier in the AST to the declaration
+//! it refers to.
+//!
+//! The resolution is a side table keyed by source positions, used to point diagnostics at
+//! declarations. The AST is not lowered, and the analyzer and transpiler still look names up by
+//! string.
+
+use std::collections::HashMap;
+
+use parser::types::{
+    Expr, Expression, Parameter, Program, Statement, Stmt, Visitor, walk_expression, walk_statement,
+};
+
+/// Identifies a declared symbol. IDs are unique within one [`Resolution`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SymbolId(pub usize);
+
+/// The kind of declaration a symbol comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    /// A variable declared with a type or `let`.
+    Variable,
+    /// A constant declared with `const`.
+    Constant,
+    /// A parameter of a function, method or lambda.
+    Parameter,
+    /// A top-level function.
+    Function,
+    /// A top-level class.
+    Class,
+}
+
+/// A declared name, together with where it was declared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    /// The declared name.
+    pub name: String,
+    /// The kind of declaration.
+    pub kind: SymbolKind,
+    /// The line and column of the declaration.
+    pub declared_at: (usize, usize),
+}
+
+/// The result of name resolution: every declared symbol, and the symbol each identifier refers
+/// to, keyed by the start of the identifier's span.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Resolution {
+    /// The declared symbols, indexed by their [`SymbolId`].
+    pub symbols: Vec<Symbol>,
+    /// The symbols referred to by identifiers, by the line and column the identifier starts at.
+    pub references: HashMap<(usize, usize), SymbolId>,
+}
+
+impl Resolution {
+    /// Returns the symbol with the given ID.
+    #[must_use]
+    pub fn symbol(&self, id: SymbolId) -> Option<&Symbol> {
+        self.symbols.get(id.0)
+    }
+
+    /// Returns the symbol referred to by the identifier starting at the given line and column.
+    #[must_use]
+    pub fn reference_at(&self, loc: (usize, usize)) -> Option<&Symbol> {
+        self.references.get(&loc).and_then(|id| self.symbol(*id))
+    }
+}
+
+/// Resolves every identifier in the program to its declaration.
+///
+/// Top-level functions and classes are visible everywhere, other names only after their
+/// declaration, following the scoping rules of the semantic analysis. Identifiers that don't
+/// resolve are left out, as the semantic analysis reports them.
+#[must_use]
+pub fn resolve(program: &Program) -> Resolution {
+    let mut resolver: Resolver = Resolver {
+        resolution: Resolution::default(),
+        scopes: vec![HashMap::new()],
+    };
+
+    for statement in &program.statements {
+        match &statement.node {
+            Statement::FunctionDeclaration { name, .. } => {
+                resolver.declare(name, SymbolKind::Function, statement.span.start);
+            }
+            Statement::ClassDeclaration { name, .. } => {
+                resolver.declare(name, SymbolKind::Class, statement.span.start);
+            }
+            _ => {}
+        }
+    }
+
+    resolver.visit_program(program);
+    resolver.resolution
+}
+
+struct Resolver {
+    resolution: Resolution,
+    /// The names visible in each nested scope, innermost last.
+    scopes: Vec<HashMap<String, SymbolId>>,
+}
+
+impl Resolver {
+    fn declare(&mut self, name: &str, kind: SymbolKind, declared_at: (usize, usize)) {
+        let id: SymbolId = SymbolId(self.resolution.symbols.len());
+        self.resolution.symbols.push(Symbol {
+            name: name.to_string(),
+            kind,
+            declared_at,
+        });
+
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), id);
+        }
+    }
+
+    /// Visits the body of a function, method or lambda in a new scope with its parameters.
+    fn body(&mut self, parameters: &[Parameter], body: &[Stmt]) {
+        self.scopes.push(HashMap::new());
+        for parameter in parameters {
+            self.declare(&parameter.name, SymbolKind::Parameter, parameter.span.start);
+        }
+        for statement in body {
+            self.visit_statement(statement);
+        }
+        self.scopes.pop();
+    }
+}
+
+impl Visitor for Resolver {
+    fn visit_statement(&mut self, statement: &Stmt) {
+        match &statement.node {
+            Statement::VariableDeclaration { name, .. } => {
+                walk_statement(self, statement);
+                self.declare(name, SymbolKind::Variable, statement.span.start);
+            }
+            Statement::ConstDeclaration { name, .. } => {
+                walk_statement(self, statement);
+                self.declare(name, SymbolKind::Constant, statement.span.start);
+            }
+            Statement::DestructuringDeclaration { names, .. } => {
+                walk_statement(self, statement);
+                for name in names {
+                    self.declare(name, SymbolKind::Variable, statement.span.start);
+                }
+            }
+            Statement::FunctionDeclaration {
+                parameters, body, ..
+            }
+            | Statement::MethodDeclaration {
+                parameters, body, ..
+            } => self.body(parameters, body),
+            Statement::ClassDeclaration { body, .. } => {
+                // Fields and constants are accessed through the class or `self`, so only the
+                // expressions of their values are resolved
+                for member in body {
+                    match &member.node {
+                        Statement::ConstDeclaration { .. } | Statement::FieldDeclaration { .. } => {
+                            walk_statement(self, member);
+                        }
+                        _ => self.visit_statement(member),
+                    }
+                }
+            }
+            Statement::Try {
+                body,
+                error_name,
+                handler,
+            } => {
+                for statement in body {
+                    self.visit_statement(statement);
+                }
+                self.declare(error_name, SymbolKind::Variable, statement.span.start);
+                for statement in handler {
+                    self.visit_statement(statement);
+                }
+            }
+            _ => walk_statement(self, statement),
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &Expr) {
+        match &expression.node {
+            Expression::Identifier(name) => {
+                if let Some(id) = self.scopes.iter().rev().find_map(|scope| scope.get(name)) {
+                    self.resolution
+                        .references
+                        .insert(expression.span.start, *id);
+                }
+            }
+            Expression::Lambda { parameters, body } => {
+                self.body(parameters, body);
+            }
+            _ => walk_expression(self, expression),
+        }
+    }
+}
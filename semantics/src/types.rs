@@ -1,8 +1,12 @@
 //! Contains the types used in the semantic analysis of the language.
 
-use std::collections::HashMap;
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
-use parser::types::{Expr, Stmt};
+use parser::types::{Expr, Parameter, Stmt};
 
 use crate::errors::{SemanticError, SemanticErrorType};
 
@@ -12,6 +16,9 @@ pub type StatementReturn = Result<(), SemanticError>;
 /// Represents the result of analyzing an expression, which has a type which will be returned.
 pub type ExpressionReturn = Result<Type, SemanticError>;
 
+/// Represents the fields and methods a class inherits from its superclass.
+pub type InheritedMembers = (HashMap<String, Field>, HashMap<String, Vec<Function>>);
+
 /// Holds information for the `method_signature` method to avoid `too_many_arguments` lint.
 pub struct MethodDeclarationSignatureInfo {
     /// The class this method belongs to.
@@ -20,21 +27,25 @@ pub struct MethodDeclarationSignatureInfo {
     pub return_type: String,
     /// The name of the method.
     pub name: String,
-    /// The parameters of the method `(Type, Identifier)`.
-    pub parameters: Vec<(String, String)>,
+    /// The parameters of the method.
+    pub parameters: Vec<Parameter>,
     /// Whether or not the method is static.
     pub static_: bool,
 }
 
 /// Holds information the `method_signature` method will return
-pub struct MethodDeclarationSignatureReturn(pub Vec<(Type, String)>, pub Type, pub bool);
+pub struct MethodDeclarationSignatureReturn(
+    pub Vec<(Type, String, (usize, usize))>,
+    pub Type,
+    pub bool,
+);
 
 /// Holds information for the `method_body` method.
 pub struct MethodDeclarationBodyInfo {
     /// The return type of the method.
     pub return_type: Type,
-    /// The parameters of the method.
-    pub parameters: Vec<(Type, String)>,
+    /// The parameters of the method `(Type, Identifier, Location)`.
+    pub parameters: Vec<(Type, String, (usize, usize))>,
     /// The body of the method.
     pub body: Vec<Stmt>,
     /// Whether or not the method is a constructor.
@@ -51,6 +62,8 @@ pub struct FieldDeclarationInfo {
     pub name: String,
     /// Whether or not the field is static.
     pub static_: bool,
+    /// Whether or not the field is a constant.
+    pub const_: bool,
     /// The initial value of the field (if static).
     pub value: Option<Expr>,
 }
@@ -83,6 +96,8 @@ pub struct Variable {
     pub var_type: Type,
     /// Whether or not the variable has been initialized
     pub initialized: bool,
+    /// Whether or not the variable is a constant, which cannot be reassigned
+    pub is_const: bool,
 }
 
 /// Represents a function
@@ -90,21 +105,80 @@ pub struct Variable {
 pub struct Function {
     /// The types of the function's parameters, in order
     pub parameters: Vec<Type>,
+    /// The names of the function's parameters, in order (empty for builtin methods)
+    pub parameter_names: Vec<String>,
     /// The return type of the function
     pub return_type: Type,
     /// Whether or not the function is a static method
     pub is_static: bool,
 }
 
+impl Function {
+    /// Puts the argument types of a call into parameter order, placing each named argument at
+    /// the position of the parameter with the same name.
+    ///
+    /// # Errors
+    /// - `SemanticErrorType::UnknownParameter`: If a named argument doesn't match any parameter.
+    /// - `SemanticErrorType::DuplicateArgument`: If a parameter is given more than one argument.
+    /// - `SemanticErrorType::MissingArgument`: If a parameter before a named argument was left
+    ///   without an argument.
+    pub fn arrange_arguments(
+        &self,
+        name: &str,
+        positional: &[Type],
+        named: &[(String, Type)],
+    ) -> Result<Vec<Type>, SemanticErrorType> {
+        let mut slots: Vec<Option<Type>> = positional.iter().cloned().map(Some).collect();
+
+        for (argument_name, argument_type) in named {
+            let index: usize = self
+                .parameter_names
+                .iter()
+                .position(|p| p == argument_name)
+                .ok_or_else(|| SemanticErrorType::UnknownParameter {
+                    func: name.into(),
+                    parameter: argument_name.clone(),
+                })?;
+
+            if slots.len() <= index {
+                slots.resize(index + 1, None);
+            }
+
+            if slots[index].is_some() {
+                return Err(SemanticErrorType::DuplicateArgument {
+                    func: name.into(),
+                    parameter: argument_name.clone(),
+                });
+            }
+
+            slots[index] = Some(argument_type.clone());
+        }
+
+        slots
+            .into_iter()
+            .enumerate()
+            .map(|(i, slot)| {
+                slot.ok_or_else(|| SemanticErrorType::MissingArgument {
+                    func: name.into(),
+                    parameter: self.parameter_names[i].clone(),
+                })
+            })
+            .collect()
+    }
+}
+
 /// Represents a user-defined class
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Class {
     /// The name of the class
     pub name: String,
-    /// Fields defined in the class, storing type and whether or not they're static
+    /// Fields defined in the class, storing type and whether or not they're static, including
+    /// inherited ones
     pub fields: HashMap<String, Field>,
-    /// Methods defined in the class and their overloads
+    /// Methods defined in the class and their overloads, including inherited ones
     pub methods: HashMap<String, Vec<Function>>,
+    /// The name of the class this class inherits from, if any
+    pub superclass: Option<String>,
 }
 
 impl Class {
@@ -142,6 +216,106 @@ impl Class {
                 column: loc.1,
             })
     }
+
+    /// Tries to get the method with the given name that accepts the given positional and named
+    /// arguments. Arguments may be subclasses of the parameter types, and if several overloads
+    /// accept them, the most specific one is used.
+    ///
+    /// # Errors
+    /// - `SemanticErrorType::MethodNotFound`: If no method with the given name exists in the
+    ///   class definition.
+    /// - `SemanticErrorType::MethodOverloadNotFound`: If no overload accepts the arguments.
+    /// - `SemanticErrorType::AmbiguousMethodCall`: If several overloads accept the arguments and
+    ///   none of them is the most specific.
+    /// - `SemanticErrorType::ArgumentCountMismatch`: If the method has a single overload that
+    ///   takes a different number of arguments.
+    /// - Errors of [`Function::arrange_arguments`] if the method has a single overload whose
+    ///   parameters the named arguments don't fit.
+    pub fn resolve_method(
+        &self,
+        scope: &Scope,
+        method_name: &str,
+        positional: &[Type],
+        named: &[(String, Type)],
+        loc: (usize, usize),
+    ) -> Result<&Function, SemanticError> {
+        let methods: &Vec<Function> =
+            self.methods.get(method_name).ok_or_else(|| SemanticError {
+                error_type: SemanticErrorType::MethodNotFound {
+                    class: self.name.clone(),
+                    method: method_name.into(),
+                },
+                line: loc.0,
+                column: loc.1,
+            })?;
+
+        if let [method] = methods.as_slice() {
+            let error_type: Option<SemanticErrorType> =
+                match method.arrange_arguments(method_name, positional, named) {
+                    Err(error_type) => Some(error_type),
+                    Ok(arguments) if arguments.len() != method.parameters.len() => {
+                        Some(SemanticErrorType::ArgumentCountMismatch {
+                            func: method_name.into(),
+                            expected: method.parameters.len(),
+                            found: arguments.len(),
+                        })
+                    }
+                    Ok(_) => None,
+                };
+
+            if let Some(error_type) = error_type {
+                return Err(SemanticError {
+                    error_type,
+                    line: loc.0,
+                    column: loc.1,
+                });
+            }
+        }
+
+        let candidates: Vec<&Function> = methods
+            .iter()
+            .filter(|m: &&Function| {
+                m.arrange_arguments(method_name, positional, named)
+                    .is_ok_and(|arguments| scope.accepts(&m.parameters, &arguments))
+            })
+            .collect();
+
+        // An overload is the most specific if every other candidate accepts its parameter types
+        let mut most_specific = candidates.iter().filter(|m| {
+            candidates
+                .iter()
+                .all(|other| scope.accepts(&other.parameters, &m.parameters))
+        });
+
+        if let (Some(method), None) = (most_specific.next(), most_specific.next()) {
+            return Ok(method);
+        }
+
+        let argument_types: Vec<String> = positional
+            .iter()
+            .chain(named.iter().map(|(_, t)| t))
+            .map(Into::into)
+            .collect();
+        let error_type: SemanticErrorType = if candidates.is_empty() {
+            SemanticErrorType::MethodOverloadNotFound {
+                class: self.name.clone(),
+                method: method_name.into(),
+                argument_types,
+            }
+        } else {
+            SemanticErrorType::AmbiguousMethodCall {
+                class: self.name.clone(),
+                method: method_name.into(),
+                argument_types,
+            }
+        };
+
+        Err(SemanticError {
+            error_type,
+            line: loc.0,
+            column: loc.1,
+        })
+    }
 }
 
 /// Represents a field in a class, storing the field's type, whether or not it is static, and
@@ -152,6 +326,20 @@ pub struct Field {
     pub field_type: Type,
     /// Whether or not the field is static
     pub is_static: bool,
+    /// Whether or not the field is a constant, which cannot be reassigned
+    pub is_const: bool,
+}
+
+/// Decides whether operators accept an `int` and a `float` operand, converting the `int` to a
+/// `float`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumericCoercion {
+    /// Mixing `int` and `float` operands is allowed and results in a `float`.
+    #[default]
+    Coerce,
+    /// Mixing `int` and `float` operands is an error, the conversion has to be written out with
+    /// `toFloat()` or `toInt()`.
+    Strict,
 }
 
 /// Represents a type in the language
@@ -182,7 +370,7 @@ impl From<&Type> for String {
             Type::String => "string".to_string(),
             Type::Void => "void".to_string(),
             Type::Class(class_name) => class_name.clone(),
-            Type::SelfType => "self".to_string(),
+            Type::SelfType => "Self".to_string(),
         }
     }
 }
@@ -207,15 +395,85 @@ impl From<&String> for Type {
     }
 }
 
+/// Records where a variable or function was declared, whether it has been read since, the last
+/// store to it that hasn't been read yet and the stores that were overwritten before being read.
+///
+/// Clones share this state, so reads through a cloned parent scope are seen by the original scope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Usage {
+    /// The location of the declaration in the source code.
+    pub loc: (usize, usize),
+    read: Rc<Cell<bool>>,
+    store: Rc<Cell<Option<Store>>>,
+    overwritten: Rc<RefCell<Vec<(usize, usize)>>>,
+}
+
+/// A value stored to a variable by a declaration or assignment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Store {
+    /// The location of the declaration or assignment in the source code.
+    pub loc: (usize, usize),
+    /// The block the store happened in, unique within a function or method body.
+    pub block: usize,
+    /// Whether or not the store happened inside a loop body.
+    pub in_loop: bool,
+}
+
+impl Usage {
+    fn new(loc: (usize, usize)) -> Self {
+        Self {
+            loc,
+            read: Rc::new(Cell::new(false)),
+            store: Rc::new(Cell::new(None)),
+            overwritten: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    fn mark_read(&self) {
+        self.read.set(true);
+        self.store.set(None);
+    }
+
+    /// Records a new store, returning the previous one if it hasn't been read.
+    #[must_use]
+    pub fn replace_store(&self, store: Store) -> Option<Store> {
+        self.store.replace(Some(store))
+    }
+
+    /// Records that the store at `loc` was overwritten before being read.
+    pub fn add_overwritten(&self, loc: (usize, usize)) {
+        self.overwritten.borrow_mut().push(loc);
+    }
+
+    /// Returns the locations of the stores that were overwritten before being read.
+    #[must_use]
+    pub fn overwritten(&self) -> Vec<(usize, usize)> {
+        self.overwritten.borrow().clone()
+    }
+
+    /// Returns the last store if it hasn't been read.
+    #[must_use]
+    pub fn unread_store(&self) -> Option<Store> {
+        self.store.get()
+    }
+
+    /// Whether or not the declaration has been read.
+    #[must_use]
+    pub fn is_read(&self) -> bool {
+        self.read.get()
+    }
+}
+
 /// Represents a scope containing all variables and functions defined in it as well as the parent
 /// scope (if any)
 #[derive(Debug, Clone, PartialEq)]
 #[allow(missing_docs)]
 pub struct Scope {
-    pub parent: Option<Box<Scope>>,
+    pub parent: Option<Box<Self>>,
     pub variables: HashMap<String, Variable>,
     pub functions: HashMap<String, Function>,
     pub classes: HashMap<String, Class>,
+    pub usages: HashMap<String, Usage>,
 }
 
 impl Scope {
@@ -230,6 +488,7 @@ impl Scope {
             variables: HashMap::new(),
             functions: HashMap::new(),
             classes: HashMap::new(),
+            usages: HashMap::new(),
         }
     }
 
@@ -250,18 +509,82 @@ impl Scope {
         loc: (usize, usize),
     ) -> Result<(), SemanticError> {
         self.check_shadowing(&name, ShadowingCheck::Variable, loc)?;
+        self.usages.insert(name.clone(), Usage::new(loc));
         self.variables.insert(
             name,
             Variable {
                 var_type,
                 initialized: false,
+                is_const: false,
             },
         );
         Ok(())
     }
 
+    /// Tries to add a new, already initialized constant to the current scope.
+    ///
+    /// # Parameters
+    /// - `name`: The name of the constant to add.
+    /// - `var_type`: The type of the constant to add.
+    /// - `loc`: Location in the source code, used for errors.
+    ///
+    /// # Errors
+    /// - `SemanticErrorType::ShadowingFunction`: If a function with the same name already exists in
+    ///   the current scope.
+    pub fn add_constant(
+        &mut self,
+        name: String,
+        var_type: Type,
+        loc: (usize, usize),
+    ) -> Result<(), SemanticError> {
+        self.check_shadowing(&name, ShadowingCheck::Variable, loc)?;
+        self.usages.insert(name.clone(), Usage::new(loc));
+        self.variables.insert(
+            name,
+            Variable {
+                var_type,
+                initialized: true,
+                is_const: true,
+            },
+        );
+        Ok(())
+    }
+
+    /// Checks whether the variable with the given name is a constant, searching through parent
+    /// scopes if necessary. Returns false if the variable does not exist.
+    #[must_use]
+    pub fn is_constant(&self, name: &str) -> bool {
+        self.variables.get(name).map_or_else(
+            || {
+                self.parent
+                    .as_ref()
+                    .is_some_and(|parent_scope| parent_scope.is_constant(name))
+            },
+            |var| var.is_const,
+        )
+    }
+
+    /// Returns the names of the variables in the current scope that are initialized.
+    #[must_use]
+    pub fn initialized_variables(&self) -> HashSet<String> {
+        self.variables
+            .iter()
+            .filter(|(_, var)| var.initialized)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Marks exactly the variables in the current scope whose names are in `initialized` as
+    /// initialized. Constants always stay initialized.
+    pub fn set_initialized_variables(&mut self, initialized: &HashSet<String>) {
+        for (name, var) in &mut self.variables {
+            var.initialized = var.is_const || initialized.contains(name);
+        }
+    }
+
     /// Check if the assigned value's type matches the variable's type and mark the variable as
-    /// initialized if not already.
+    /// initialized if not already. Variables of parent scopes are assigned in the scope that
+    /// declares them.
     ///
     /// # Parameters
     /// - `name`: The name of the variable being assigned to.
@@ -275,6 +598,7 @@ impl Scope {
     ///   any parent scope.
     /// - `SemanticErrorType::VariableUninitialized`: If the variable is found but hasn't been
     ///   initialized yet.
+    /// - `SemanticErrorType::ConstantReassignment`: If the variable is a constant.
     ///
     /// # Panics
     /// Panics if the variable is first found but then... not
@@ -284,9 +608,21 @@ impl Scope {
         value_type: &Type,
         loc: (usize, usize),
     ) -> Result<(), SemanticError> {
-        let var_type: Type = self.get_local_variable(name, loc)?;
+        if !self.variables.contains_key(name)
+            && let Some(parent_scope) = self.parent.as_mut()
+        {
+            return parent_scope.assign_variable(name, value_type, loc);
+        }
+
+        let var_type: Type = self.get_declared_variable(name, loc)?;
 
-        if var_type == *value_type {
+        if self.variables.get(name).is_some_and(|var| var.is_const) {
+            Err(SemanticError {
+                error_type: SemanticErrorType::ConstantReassignment(name.to_string()),
+                line: loc.0,
+                column: loc.1,
+            })
+        } else if self.is_assignable(value_type, &var_type) {
             self.variables
                 .get_mut(name)
                 .expect("Checked before")
@@ -330,6 +666,7 @@ impl Scope {
                     )
                 },
                 |var| {
+                    self.mark_read(name);
                     if var.initialized {
                         Ok(var.var_type.clone())
                     } else {
@@ -344,26 +681,34 @@ impl Scope {
             })
     }
 
-    /// Get the type of a variable in the current scope by its name.
+    /// Get the type of a variable by its name, searching through parent scopes if necessary.
+    /// Unlike [`Scope::get_variable`], the variable is neither marked as read nor required to be
+    /// initialized, which is what assigning to it needs.
     ///
     /// # Parameters
     /// - `name`: The name of the variable to look up.
     /// - `loc`: Location in the source code, used for errors.
     ///
     /// # Errors
-    /// - `SemanticErrorType::VariableNotFound`: If the variable is not found in the current scope
-    pub fn get_local_variable(
+    /// - `SemanticErrorType::VariableNotFound`: If the variable is not found in the current scope or
+    ///   any parent scope.
+    pub fn get_declared_variable(
         &self,
         name: &str,
         loc: (usize, usize),
     ) -> Result<Type, SemanticError> {
         self.variables.get(name).map_or_else(
             || {
-                Err(SemanticError {
-                    error_type: SemanticErrorType::VariableNotFound(name.to_string()),
-                    line: loc.0,
-                    column: loc.1,
-                })
+                self.parent.as_ref().map_or_else(
+                    || {
+                        Err(SemanticError {
+                            error_type: SemanticErrorType::VariableNotFound(name.to_string()),
+                            line: loc.0,
+                            column: loc.1,
+                        })
+                    },
+                    |parent_scope| parent_scope.get_declared_variable(name, loc),
+                )
             },
             |var| Ok(var.var_type.clone()),
         )
@@ -388,6 +733,7 @@ impl Scope {
         loc: (usize, usize),
     ) -> Result<(), SemanticError> {
         self.check_shadowing(&name, ShadowingCheck::Function, loc)?;
+        self.usages.insert(name.clone(), Usage::new(loc));
         self.functions.insert(name, function);
         Ok(())
     }
@@ -415,7 +761,10 @@ impl Scope {
                         },
                     )
                 },
-                |function| Ok(function.clone()),
+                |function| {
+                    self.mark_read(name);
+                    Ok(function.clone())
+                },
             )
             .map_err(|e| SemanticError {
                 error_type: e,
@@ -551,6 +900,7 @@ impl Scope {
     /// - `SemanticErrorType::TypeMismatch`: If the type of the value being assigned to the field
     ///   does not match the field's type.
     /// - `SemanticErrorType::FieldNotFound`: If the field is not found in the class definition.
+    /// - `SemanticErrorType::ConstantReassignment`: If the field is a constant.
     pub fn assign_field(
         &mut self,
         class_name: &str,
@@ -560,7 +910,13 @@ impl Scope {
     ) -> Result<(), SemanticError> {
         let field: Field = self.get_class_field(class_name, field_name, loc)?;
 
-        if field.field_type == *value_type {
+        if field.is_const {
+            Err(SemanticError {
+                error_type: SemanticErrorType::ConstantReassignment(field_name.to_string()),
+                line: loc.0,
+                column: loc.1,
+            })
+        } else if self.is_assignable(value_type, &field.field_type) {
             Ok(())
         } else {
             Err(SemanticError {
@@ -574,6 +930,66 @@ impl Scope {
         }
     }
 
+    /// Checks whether a value of type `value_type` can be stored where `target` is expected, which
+    /// is the case if the types are equal or `value_type` is a class inheriting from `target`.
+    #[must_use]
+    pub fn is_assignable(&self, value_type: &Type, target: &Type) -> bool {
+        if value_type == target {
+            return true;
+        }
+
+        let Type::Class(class_name) = value_type else {
+            return false;
+        };
+
+        self.get_class(class_name, (0, 0))
+            .ok()
+            .and_then(|class| class.superclass)
+            .is_some_and(|superclass| self.is_assignable(&Type::Class(superclass), target))
+    }
+
+    /// Checks whether a function with the given parameter types can be called with arguments of
+    /// the given types, each of which must be assignable to its parameter.
+    #[must_use]
+    pub fn accepts(&self, parameters: &[Type], arguments: &[Type]) -> bool {
+        parameters.len() == arguments.len()
+            && arguments
+                .iter()
+                .zip(parameters)
+                .all(|(argument, parameter)| self.is_assignable(argument, parameter))
+    }
+
+    /// Returns the names and usages of the variables and functions declared in this scope that
+    /// have never been read, ordered by their location. Names starting with `_` are left out.
+    #[must_use]
+    pub fn unread(&self) -> Vec<(&String, &Usage)> {
+        let mut unread: Vec<(&String, &Usage)> = self
+            .usages
+            .iter()
+            .filter(|(name, usage)| !name.starts_with('_') && !usage.is_read())
+            .collect();
+        unread.sort_by_key(|(_, usage)| usage.loc);
+        unread
+    }
+
+    /// Returns the usage of the variable or function with the given name, searching through parent
+    /// scopes if necessary.
+    #[must_use]
+    pub fn usage(&self, name: &str) -> Option<&Usage> {
+        self.usages.get(name).or_else(|| {
+            self.parent
+                .as_ref()
+                .and_then(|parent_scope| parent_scope.usage(name))
+        })
+    }
+
+    /// Marks the variable or function with the given name in this scope as read.
+    pub fn mark_read(&self, name: &str) {
+        if let Some(usage) = self.usages.get(name) {
+            usage.mark_read();
+        }
+    }
+
     fn check_shadowing(
         &self,
         name: &str,
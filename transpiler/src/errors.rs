@@ -0,0 +1,87 @@
+//! Contains the errors that can come up during transpilation
+
+use parser::{errors::with_source, types::Span};
+
+/// Represents an error that can occur during transpilation, including the type of error and the
+/// span of the offending node in the source code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranspileError {
+    /// The type of transpile error that occurred.
+    pub error_type: TranspileErrorType,
+    /// The span of the statement or expression that caused the error.
+    pub span: Span,
+}
+
+impl TranspileError {
+    /// Creates an [`TranspileErrorType::Unsupported`] error for the given language feature, e.g.
+    /// `Arrays`, spanning the given node.
+    #[must_use]
+    pub fn unsupported(feature: &str, span: &Span) -> Self {
+        Self {
+            error_type: TranspileErrorType::Unsupported(feature.to_string()),
+            span: span.clone(),
+        }
+    }
+
+    /// Returns the full error message.
+    #[must_use]
+    pub fn error_message(&self) -> String {
+        let mut message: String = String::new();
+
+        message.push_str("TranspileError: ");
+        message.push_str(self.error_type.error_name());
+        message.push_str(" at [");
+        message.push_str(&self.span.start.0.to_string());
+        message.push(':');
+        message.push_str(&self.span.start.1.to_string());
+        message.push_str("]: ");
+        message.push_str(&self.error_type.message());
+
+        message
+    }
+
+    /// Returns the full error message, followed by the offending line of the given source code
+    /// with a caret below the start of the node.
+    #[must_use]
+    pub fn error_message_with_source(&self, source: &str) -> String {
+        with_source(
+            self.error_message(),
+            source,
+            self.span.start.0,
+            self.span.start.1,
+        )
+    }
+
+    /// Prints the error message to stderr.
+    pub fn print(&self) {
+        eprintln!("{}", self.error_message());
+    }
+}
+
+/// Represents an error that can occur during transpilation. Programs that passed semantic
+/// analysis can only fail here by using features the C# backend doesn't implement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranspileErrorType {
+    /// User used a language feature the C# transpiler doesn't support yet, e.g. arrays.
+    Unsupported(String),
+}
+
+impl TranspileErrorType {
+    /// Returns the error message for this transpile error type.
+    #[must_use]
+    pub fn message(&self) -> String {
+        match self {
+            Self::Unsupported(feature) => {
+                format!("{feature} are not supported by the C# transpiler yet")
+            }
+        }
+    }
+
+    /// Returns the name of the transpile error type.
+    #[must_use]
+    pub const fn error_name(&self) -> &str {
+        match self {
+            Self::Unsupported(_) => "Unsupported",
+        }
+    }
+}
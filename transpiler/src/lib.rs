@@ -1,12 +1,19 @@
 //! Main library file for the compiler module
 
 mod csharp;
+pub mod errors;
+
+use std::collections::{HashMap, HashSet};
 
 use parser::types::{
-    BinaryOperator, Expr, Expression, Literal, Program, Statement, Stmt, UnaryOperator,
+    BinaryOperator, Expr, Expression, Literal, Parameter, Program, Statement, Stmt, UnaryOperator,
+    Visitor, walk_expression,
 };
 
-use crate::csharp::{Type, prefix};
+use crate::{
+    csharp::{Type, prefix},
+    errors::TranspileError,
+};
 
 /// Transpiler struct responsible for transpiling source code into C# code
 #[derive(Debug, Clone)]
@@ -17,18 +24,59 @@ pub struct Transpiler {
     indent_level: usize,
     /// Class declarations to be added at the end of the output
     class_declarations: String,
+    /// Inheritance information about the classes declared in the program, by class name
+    classes: HashMap<String, ClassInfo>,
+    /// The path of the source file, if statements should be mapped back to it with `#line`
+    source_file: Option<String>,
+}
+
+/// Information about a declared class needed to lower inheritance to C#
+#[derive(Debug, Clone, Default)]
+struct ClassInfo {
+    /// The class this class inherits from, if any
+    superclass: Option<String>,
+    /// The names and parameter types of the instance methods declared in the class
+    methods: Vec<(String, Vec<String>)>,
+    /// The parameter types of the constructors declared in the class
+    constructors: Vec<Vec<String>>,
+    /// Whether any other class inherits from this class
+    inherited: bool,
 }
 
 impl Transpiler {
     /// Transpiles the given source code into C# code
     ///
     /// # Errors
-    /// When something goes wrong during transpilation, for example an invalid AST
-    pub fn transpile(program: Program) -> Result<String, String> {
+    /// When the program uses a feature the C# transpiler doesn't support yet
+    pub fn transpile(program: Program) -> Result<String, TranspileError> {
+        Self::transpile_program(program, None)
+    }
+
+    /// Transpiles the given source code into C# code, preceding each statement with a `#line`
+    /// directive pointing at its line in the given source file.
+    ///
+    /// This makes the C# compiler and debuggers refer to the source file instead of the generated
+    /// code.
+    ///
+    /// # Errors
+    /// When the program uses a feature the C# transpiler doesn't support yet
+    pub fn transpile_with_line_directives(
+        program: Program,
+        source_file: &str,
+    ) -> Result<String, TranspileError> {
+        Self::transpile_program(program, Some(source_file.to_string()))
+    }
+
+    fn transpile_program(
+        program: Program,
+        source_file: Option<String>,
+    ) -> Result<String, TranspileError> {
         let mut transpiler: Self = Self {
             output: String::new(),
             indent_level: 0,
             class_declarations: String::new(),
+            classes: Self::class_infos(&program.statements),
+            source_file,
         };
 
         transpiler.output.push_str("// ENTRY POINT\n");
@@ -42,7 +90,15 @@ impl Transpiler {
             .push_str("public partial class Program {\n");
         transpiler.indent_level += 1;
 
+        // Functions the program never calls are left out of the generated code
+        let reachable: HashSet<String> = Self::reachable_functions(&program.statements);
+
         for statement in program.statements {
+            if let Statement::FunctionDeclaration { name, .. } = &statement.node
+                && !reachable.contains(name)
+            {
+                continue;
+            }
             transpiler.statement(statement)?;
         }
 
@@ -56,50 +112,207 @@ impl Transpiler {
         Ok(transpiler.output)
     }
 
+    /// Collects the names of the top-level functions that can be called from the classes declared
+    /// in the given statements, which contain the entry point `Main.main`, directly or through
+    /// other functions
+    fn reachable_functions(statements: &[Stmt]) -> HashSet<String> {
+        let mut functions: HashMap<&str, &Stmt> = HashMap::new();
+        let mut references: References = References::default();
+
+        for statement in statements {
+            if let Statement::FunctionDeclaration { name, .. } = &statement.node {
+                functions.insert(name, statement);
+            } else {
+                references.visit_statement(statement);
+            }
+        }
+
+        let mut reachable: HashSet<String> = HashSet::new();
+        let mut pending: Vec<String> = references.0;
+
+        while let Some(name) = pending.pop() {
+            if let Some(declaration) = functions.get(name.as_str())
+                && reachable.insert(name)
+            {
+                let mut references: References = References::default();
+                references.visit_statement(declaration);
+                pending.extend(references.0);
+            }
+        }
+
+        reachable
+    }
+
+    /// Collects the inheritance information of all classes declared in the given statements
+    fn class_infos(statements: &[Stmt]) -> HashMap<String, ClassInfo> {
+        let mut classes: HashMap<String, ClassInfo> = HashMap::new();
+
+        for statement in statements {
+            let Statement::ClassDeclaration {
+                name,
+                superclass,
+                body,
+                ..
+            } = &statement.node
+            else {
+                continue;
+            };
+
+            let info: &mut ClassInfo = classes.entry(name.clone()).or_default();
+            info.superclass.clone_from(superclass);
+
+            for member in body {
+                if let Statement::MethodDeclaration {
+                    return_type,
+                    name,
+                    parameters,
+                    static_,
+                    ..
+                } = &member.node
+                {
+                    let parameter_types: Vec<String> = parameters
+                        .iter()
+                        .map(|parameter| parameter.type_.clone())
+                        .collect();
+
+                    if return_type.is_empty() {
+                        info.constructors.push(parameter_types);
+                    } else if !static_ {
+                        info.methods.push((name.clone(), parameter_types));
+                    }
+                }
+            }
+
+            if let Some(superclass) = superclass {
+                classes.entry(superclass.clone()).or_default().inherited = true;
+            }
+        }
+
+        classes
+    }
+
+    /// Returns the C# modifier needed for an instance method of the given class, which is
+    /// `override` if a superclass declares the same method, `virtual` if the class is inherited
+    /// from, and empty otherwise.
+    fn method_modifier(&self, class_name: &str, name: &str, parameters: &[String]) -> &'static str {
+        let mut superclass: Option<&String> = self
+            .classes
+            .get(class_name)
+            .and_then(|class| class.superclass.as_ref());
+
+        while let Some(class) = superclass.and_then(|superclass| self.classes.get(superclass)) {
+            if class
+                .methods
+                .iter()
+                .any(|(method, types)| method == name && types == parameters)
+            {
+                return "override ";
+            }
+            superclass = class.superclass.as_ref();
+        }
+
+        if self
+            .classes
+            .get(class_name)
+            .is_some_and(|class| class.inherited)
+        {
+            "virtual "
+        } else {
+            ""
+        }
+    }
+
     fn indent(&mut self) {
         for _ in 0..self.indent_level {
             self.output.push_str("  ");
         }
     }
 
-    fn expr_path(src: Expr) -> Result<String, String> {
+    /// Writes the given expression as the object of a member access or the target of an
+    /// assignment, parenthesizing anything that isn't a plain path
+    fn expression_path(&mut self, src: Expr) -> Result<(), TranspileError> {
         match src.node {
-            Expression::Identifier(identifier) => Ok(prefix(&identifier)),
-            Expression::Call { callee, arguments } => {
-                let mut args: Vec<String> = Vec::new();
-
-                let mut arg_compiler: Self = Self {
-                    output: String::new(),
-                    indent_level: 0,
-                    class_declarations: String::new(),
-                };
-
-                for arg in &arguments {
-                    arg_compiler.expression(arg.clone())?;
-                    args.push(arg_compiler.output.clone());
-                    arg_compiler.output.clear();
-                }
-
-                Ok(format!(
-                    "{}({})",
-                    Self::expr_path(*callee)?,
-                    args.join(", ")
-                ))
-            }
+            Expression::Identifier(identifier) => self.output.push_str(&prefix(&identifier)),
             Expression::MemberAccess { object, member } => {
-                Ok(format!("{}.{}", Self::expr_path(*object)?, prefix(&member)))
+                self.expression_path(*object)?;
+                self.output.push('.');
+                self.output.push_str(&prefix(&member));
             }
-            Expression::Self_ => Ok(String::from("this")),
-            _ => Err(format!("Unsupported expression in path: {src:?}")),
+            Expression::Self_ => self.output.push_str("this"),
+            _ => {
+                self.output.push('(');
+                self.expression(src)?;
+                self.output.push(')');
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the statements of a function, method or block body, one level deeper than the
+    /// statement that owns it
+    fn body(&mut self, body: Vec<Stmt>) -> Result<(), TranspileError> {
+        self.indent_level += 1;
+        for stmt in body {
+            self.statement(stmt)?;
+        }
+        self.indent_level -= 1;
+        Ok(())
+    }
+
+    /// Emits a `#line` directive mapping the following line to the start of the given statement,
+    /// if a source file is set.
+    fn line_directive(&mut self, statement: &Stmt) {
+        if let Some(source_file) = &self.source_file {
+            self.output.push_str("#line ");
+            self.output.push_str(&statement.span.start.0.to_string());
+            self.output.push_str(" \"");
+            self.output.push_str(source_file);
+            self.output.push_str("\"\n");
         }
     }
 
-    fn statement(&mut self, statement: Stmt) -> Result<(), String> {
+    #[allow(clippy::too_many_lines)]
+    fn statement(&mut self, statement: Stmt) -> Result<(), TranspileError> {
+        // Blocks are set apart by an empty line, which must come before the directive
+        if matches!(
+            statement.node,
+            Statement::If { .. }
+                | Statement::While { .. }
+                | Statement::DoWhile { .. }
+                | Statement::Try { .. }
+        ) {
+            self.output.push('\n');
+        }
+
+        // Classes are written to their own buffer, their members map themselves
+        if !matches!(statement.node, Statement::ClassDeclaration { .. }) {
+            self.line_directive(&statement);
+        }
+
         match statement.node {
             Statement::VariableDeclaration { type_, name, value } => {
                 self.indent();
                 self.variable_declaration_statement(&type_, &name, value)?;
             }
+            Statement::DestructuringDeclaration { .. } => {
+                return Err(TranspileError::unsupported(
+                    "Destructuring declarations",
+                    &statement.span,
+                ));
+            }
+            Statement::ConstDeclaration { type_, name, value } => {
+                self.indent();
+                self.variable_declaration_statement(&type_, &name, Some(value))?;
+            }
+            Statement::Import(_) => {
+                return Err(TranspileError::unsupported("Imports", &statement.span));
+            }
+            Statement::EnumDeclaration { .. } => {
+                return Err(TranspileError::unsupported("Enums", &statement.span));
+            }
+            Statement::InterfaceDeclaration { .. } => {
+                return Err(TranspileError::unsupported("Interfaces", &statement.span));
+            }
             Statement::FieldDeclaration {
                 type_,
                 name,
@@ -114,6 +327,7 @@ impl Transpiler {
                 name,
                 parameters,
                 body,
+                ..
             } => {
                 self.indent();
                 self.function_declaration_statement(&return_type, &name, &parameters, body)?;
@@ -125,14 +339,27 @@ impl Transpiler {
                 parameters,
                 body,
                 static_,
+                ..
             } => {
                 self.indent();
-                self.method_declaration_statement(&return_type, &name, &parameters, body, static_)?;
+                self.method_declaration_statement(
+                    &return_type,
+                    &name,
+                    &parameters,
+                    body,
+                    static_,
+                    "",
+                )?;
                 return Ok(());
             }
-            Statement::ClassDeclaration { name, body } => {
+            Statement::ClassDeclaration {
+                name,
+                superclass,
+                body,
+                ..
+            } => {
                 self.indent();
-                self.class_declaration_statement(&name, body)?;
+                self.class_declaration_statement(&name, superclass.as_deref(), body)?;
                 return Ok(());
             }
             Statement::Assignment { assignee, value } => {
@@ -143,17 +370,43 @@ impl Transpiler {
                 conditional_branches,
                 else_branch,
             } => {
-                self.if_statement(&conditional_branches, else_branch)?;
+                self.if_statement(conditional_branches, else_branch)?;
                 return Ok(());
             }
             Statement::While { condition, body } => {
-                self.while_loop_statement(condition, &body)?;
+                self.while_loop_statement(condition, body)?;
+                return Ok(());
+            }
+            Statement::DoWhile { body, condition } => {
+                self.do_while_loop_statement(body, condition)?;
+                return Ok(());
+            }
+            Statement::Try {
+                body,
+                error_name,
+                handler,
+            } => {
+                self.try_statement(body, &error_name, handler)?;
                 return Ok(());
             }
             Statement::Return(ret) => {
                 self.indent();
                 self.return_statement(ret)?;
             }
+            Statement::Break => {
+                self.indent();
+                self.output.push_str("break");
+            }
+            Statement::Continue => {
+                self.indent();
+                self.output.push_str("continue");
+            }
+            Statement::Throw(message) => {
+                self.indent();
+                self.output.push_str("throw new System.Exception(");
+                self.expression(message)?;
+                self.output.push_str(".Inner)");
+            }
             Statement::Expression(expr) => {
                 self.indent();
                 self.expression(expr)?;
@@ -169,8 +422,13 @@ impl Transpiler {
         type_: &str,
         name: &str,
         value: Option<Expr>,
-    ) -> Result<(), String> {
-        let type_: String = Type::from(type_);
+    ) -> Result<(), TranspileError> {
+        // Semantics has inferred the type of untyped declarations, which C# can do as well
+        let type_: String = if type_.is_empty() {
+            String::from("var")
+        } else {
+            Type::from(type_)
+        };
 
         self.output.push_str(&type_);
         self.output.push(' ');
@@ -190,7 +448,7 @@ impl Transpiler {
         name: &str,
         static_: bool,
         value: Option<Expr>,
-    ) -> Result<(), String> {
+    ) -> Result<(), TranspileError> {
         let type_: String = Type::from(type_.strip_prefix("##").unwrap_or(type_));
 
         self.output.push_str("public ");
@@ -209,9 +467,26 @@ impl Transpiler {
         Ok(())
     }
 
-    fn variable_assignment_statement(&mut self, assignee: Expr, value: Expr) -> Result<(), String> {
-        let expr_path = Self::expr_path(assignee)?;
-        self.output.push_str(&expr_path);
+    fn const_field_declaration_statement(
+        &mut self,
+        type_: &str,
+        name: &str,
+        value: Expr,
+    ) -> Result<(), TranspileError> {
+        self.output.push_str("public static readonly ");
+        self.output.push_str(&Type::from(type_));
+        self.output.push(' ');
+        self.output.push_str(&prefix(name));
+        self.output.push_str(" = ");
+        self.expression(value)
+    }
+
+    fn variable_assignment_statement(
+        &mut self,
+        assignee: Expr,
+        value: Expr,
+    ) -> Result<(), TranspileError> {
+        self.expression_path(assignee)?;
         self.output.push_str(" = ");
         self.expression(value)?;
         Ok(())
@@ -221,37 +496,26 @@ impl Transpiler {
         &mut self,
         return_type: &str,
         name: &str,
-        params: &[(String, String)],
+        params: &[Parameter],
         body: Vec<Stmt>,
-    ) -> Result<(), String> {
+    ) -> Result<(), TranspileError> {
         self.output.push_str("public static ");
         self.output.push_str(&Type::from(return_type));
         self.output.push(' ');
         self.output.push_str(&prefix(name));
         self.output.push('(');
 
-        for (i, (type_, parameter_name)) in params.iter().enumerate() {
-            self.output.push_str(&Type::from(type_));
+        for (i, parameter) in params.iter().enumerate() {
+            self.output.push_str(&Type::from(&parameter.type_));
             self.output.push(' ');
-            self.output.push_str(&prefix(parameter_name));
+            self.output.push_str(&prefix(&parameter.name));
             if i < params.len() - 1 {
                 self.output.push_str(", ");
             }
         }
 
         self.output.push_str(") {\n");
-
-        let mut function_compiler: Self = Self {
-            output: String::new(),
-            indent_level: self.indent_level + 1,
-            class_declarations: String::new(),
-        };
-
-        for stmt in body {
-            function_compiler.statement(stmt)?;
-        }
-
-        self.output.push_str(&function_compiler.output);
+        self.body(body)?;
         self.indent();
         self.output.push_str("}\n\n");
         Ok(())
@@ -261,11 +525,13 @@ impl Transpiler {
         &mut self,
         return_type: &str,
         name: &str,
-        params: &[(String, String)],
+        params: &[Parameter],
         body: Vec<Stmt>,
         static_: bool,
-    ) -> Result<(), String> {
+        modifier: &str,
+    ) -> Result<(), TranspileError> {
         self.output.push_str("public ");
+        self.output.push_str(modifier);
 
         let return_type: String = Type::from(return_type);
         let pname: String = prefix(name);
@@ -282,47 +548,105 @@ impl Transpiler {
         self.output.push_str(&pname);
         self.output.push('(');
 
-        for (i, (type_, parameter_name)) in params.iter().enumerate() {
-            self.output.push_str(&Type::from(type_));
+        for (i, parameter) in params.iter().enumerate() {
+            self.output.push_str(&Type::from(&parameter.type_));
             self.output.push(' ');
-            self.output.push_str(&prefix(parameter_name));
+            self.output.push_str(&prefix(&parameter.name));
             if i < params.len() - 1 {
                 self.output.push_str(", ");
             }
         }
 
         self.output.push_str(") {\n");
-
-        let mut function_compiler: Self = Self {
-            output: String::new(),
-            indent_level: self.indent_level + 1,
-            class_declarations: String::new(),
-        };
-
-        for stmt in body {
-            function_compiler.statement(stmt)?;
-        }
-
-        self.output.push_str(&function_compiler.output);
+        self.body(body)?;
         self.indent();
         self.output.push_str("}\n\n");
         Ok(())
     }
 
-    fn class_declaration_statement(&mut self, name: &str, body: Vec<Stmt>) -> Result<(), String> {
+    fn class_declaration_statement(
+        &mut self,
+        name: &str,
+        superclass: Option<&str>,
+        body: Vec<Stmt>,
+    ) -> Result<(), TranspileError> {
         self.class_declarations.push_str("class ");
         self.class_declarations.push_str(&prefix(name));
 
+        if let Some(superclass) = superclass {
+            self.class_declarations.push_str(" : ");
+            self.class_declarations.push_str(&prefix(superclass));
+        }
+
         self.class_declarations.push_str(" {\n");
 
         let mut class_compiler: Self = Self {
             output: String::new(),
             indent_level: self.indent_level + 1,
             class_declarations: String::new(),
+            classes: HashMap::new(),
+            source_file: self.source_file.clone(),
         };
 
+        // C# subclass constructors implicitly call the parameterless base constructor
+        if let Some(class) = self.classes.get(name)
+            && class.inherited
+            && !class.constructors.is_empty()
+            && !class.constructors.iter().any(Vec::is_empty)
+        {
+            class_compiler.indent();
+            class_compiler.output.push_str("protected ");
+            class_compiler.output.push_str(&prefix(name));
+            class_compiler.output.push_str("() {}\n\n");
+        }
+
         for stmt in body {
-            class_compiler.statement(stmt)?;
+            if matches!(
+                stmt.node,
+                Statement::ConstDeclaration { .. } | Statement::MethodDeclaration { .. }
+            ) {
+                class_compiler.line_directive(&stmt);
+            }
+
+            match stmt.node {
+                Statement::ConstDeclaration { type_, name, value } => {
+                    class_compiler.indent();
+                    class_compiler.const_field_declaration_statement(&type_, &name, value)?;
+                    class_compiler.output.push_str(";\n");
+                }
+                Statement::MethodDeclaration {
+                    return_type,
+                    name: method_name,
+                    parameters,
+                    body,
+                    static_,
+                    ..
+                } => {
+                    let modifier: &str = if static_ || return_type.is_empty() {
+                        ""
+                    } else {
+                        let parameter_types: Vec<String> = parameters
+                            .iter()
+                            .map(|parameter| parameter.type_.clone())
+                            .collect();
+                        self.method_modifier(name, &method_name, &parameter_types)
+                    };
+
+                    class_compiler.indent();
+                    class_compiler.method_declaration_statement(
+                        &return_type,
+                        &method_name,
+                        &parameters,
+                        body,
+                        static_,
+                        modifier,
+                    )?;
+                }
+                node => class_compiler.statement(Stmt {
+                    node,
+                    span: stmt.span,
+                })?,
+            }
         }
 
         self.class_declarations.push_str(&class_compiler.output);
@@ -332,25 +656,20 @@ impl Transpiler {
 
     fn if_statement(
         &mut self,
-        conditional_branches: &[(Expr, Vec<Stmt>)],
+        conditional_branches: Vec<(Expr, Vec<Stmt>)>,
         else_branch: Option<Vec<Stmt>>,
-    ) -> Result<(), String> {
-        self.output.push('\n');
+    ) -> Result<(), TranspileError> {
         self.indent();
 
-        for (i, (condition, body)) in conditional_branches.iter().enumerate() {
+        for (i, (condition, body)) in conditional_branches.into_iter().enumerate() {
             self.output
                 .push_str(if i == 0 { "if " } else { "else if " });
             self.output.push('(');
 
-            self.expression(condition.clone())?;
+            self.expression(condition)?;
             self.output.push_str(") {\n");
 
-            self.indent_level += 1;
-            for stmt in body {
-                self.statement(stmt.clone())?;
-            }
-            self.indent_level -= 1;
+            self.body(body)?;
 
             self.indent();
             self.output.push_str("} ");
@@ -359,11 +678,7 @@ impl Transpiler {
         if let Some(else_block) = else_branch {
             self.output.push_str("else {\n");
 
-            self.indent_level += 1;
-            for stmt in else_block {
-                self.statement(stmt.clone())?;
-            }
-            self.indent_level -= 1;
+            self.body(else_block)?;
 
             self.indent();
             self.output.push('}');
@@ -373,8 +688,11 @@ impl Transpiler {
         Ok(())
     }
 
-    fn while_loop_statement(&mut self, condition: Expr, body: &[Stmt]) -> Result<(), String> {
-        self.output.push('\n');
+    fn while_loop_statement(
+        &mut self,
+        condition: Expr,
+        body: Vec<Stmt>,
+    ) -> Result<(), TranspileError> {
         self.indent();
         self.output.push_str("while (");
 
@@ -382,9 +700,62 @@ impl Transpiler {
 
         self.output.push_str(") {\n");
 
+        self.body(body)?;
+
+        self.indent();
+        self.output.push_str("}\n\n");
+        Ok(())
+    }
+
+    fn do_while_loop_statement(
+        &mut self,
+        body: Vec<Stmt>,
+        condition: Expr,
+    ) -> Result<(), TranspileError> {
+        self.indent();
+        self.output.push_str("do {\n");
+
+        self.body(body)?;
+
+        self.indent();
+        self.output.push_str("} while (");
+
+        self.expression(condition)?;
+
+        self.output.push_str(");\n\n");
+        Ok(())
+    }
+
+    fn try_statement(
+        &mut self,
+        body: Vec<Stmt>,
+        error_name: &str,
+        handler: Vec<Stmt>,
+    ) -> Result<(), TranspileError> {
+        // User identifiers are always prefixed, so the exception variable can't collide with
+        // them, and the indent level keeps nested handlers apart
+        let exception: String = format!("__error{}", self.indent_level);
+
+        self.indent();
+        self.output.push_str("try {\n");
+
+        self.body(body)?;
+
+        self.indent();
+        self.output.push_str("} catch (System.Exception ");
+        self.output.push_str(&exception);
+        self.output.push_str(") {\n");
+
         self.indent_level += 1;
-        for stmt in body {
-            self.statement(stmt.clone())?;
+        self.indent();
+        self.output.push_str(&Type::from("string"));
+        self.output.push(' ');
+        self.output.push_str(&prefix(error_name));
+        self.output.push_str(" = new(");
+        self.output.push_str(&exception);
+        self.output.push_str(".Message);\n");
+        for stmt in handler {
+            self.statement(stmt)?;
         }
         self.indent_level -= 1;
 
@@ -393,7 +764,7 @@ impl Transpiler {
         Ok(())
     }
 
-    fn return_statement(&mut self, ret: Option<Expr>) -> Result<(), String> {
+    fn return_statement(&mut self, ret: Option<Expr>) -> Result<(), TranspileError> {
         self.output.push_str("return");
 
         if let Some(expr) = ret {
@@ -403,7 +774,7 @@ impl Transpiler {
         Ok(())
     }
 
-    fn expression(&mut self, expr: Expr) -> Result<(), String> {
+    fn expression(&mut self, expr: Expr) -> Result<(), TranspileError> {
         match expr.node {
             Expression::Literal(literal) => self.literal_expression(literal),
             Expression::Binary {
@@ -415,14 +786,34 @@ impl Transpiler {
                 self.unary_expression(&operator, *operand)?;
             }
             Expression::Identifier(identifier) => self.output.push_str(&prefix(&identifier)),
-            Expression::Call { callee, arguments } => {
-                self.function_call_expression(*callee, &arguments)?;
+            Expression::Call {
+                callee,
+                arguments,
+                named_arguments,
+            } => {
+                self.function_call_expression(*callee, arguments, named_arguments)?;
             }
             Expression::MemberAccess { object, member } => {
-                let var_name = Self::expr_path(*object)? + "." + &prefix(&member);
-                self.output.push_str(&var_name);
+                self.expression_path(*object)?;
+                self.output.push('.');
+                self.output.push_str(&prefix(&member));
             }
             Expression::Self_ => self.output.push_str("this"),
+            Expression::ArrayLiteral(_) | Expression::Index { .. } => {
+                return Err(TranspileError::unsupported("Arrays", &expr.span));
+            }
+            Expression::Tuple(_) => {
+                return Err(TranspileError::unsupported("Tuples", &expr.span));
+            }
+            Expression::MapLiteral(_) => {
+                return Err(TranspileError::unsupported("Maps", &expr.span));
+            }
+            Expression::Lambda { .. } => {
+                return Err(TranspileError::unsupported(
+                    "Lambda expressions",
+                    &expr.span,
+                ));
+            }
         }
 
         Ok(())
@@ -436,8 +827,11 @@ impl Transpiler {
                 self.output.push(')');
             }
             Literal::Float(value) => {
+                // Debug formatting keeps the decimal point and uses an exponent for large values,
+                // which are both valid C# double literals
+                let value: String = format!("{value:?}");
                 self.output.push_str("new CustomLang.Types.rmm_Float(");
-                self.output.push_str(&value.to_string());
+                self.output.push_str(&value);
                 self.output.push(')');
             }
             Literal::String(value) => {
@@ -451,6 +845,7 @@ impl Transpiler {
                 self.output.push_str(&value.to_string());
                 self.output.push(')');
             }
+            Literal::Null => self.output.push_str("null"),
         }
     }
 
@@ -459,27 +854,26 @@ impl Transpiler {
         left: Expr,
         operator: &BinaryOperator,
         right: Expr,
-    ) -> Result<(), String> {
+    ) -> Result<(), TranspileError> {
+        // Semantics only allows bools here, and C#'s operators skip the right operand if the
+        // left one decides the result
+        if let BinaryOperator::And | BinaryOperator::Or = operator {
+            self.output.push_str("new CustomLang.Types.rmm_Bool(");
+            self.expression(left)?;
+            self.output.push_str(if *operator == BinaryOperator::And {
+                ".Inner && "
+            } else {
+                ".Inner || "
+            });
+            self.expression(right)?;
+            self.output.push_str(".Inner)");
+            return Ok(());
+        }
+
         self.expression(left)?;
         self.output.push('.');
 
-        self.output.push_str(
-            &(String::from("rmm__bop")
-                + match operator {
-                    BinaryOperator::Add => "Add",
-                    BinaryOperator::Subtract => "Sub",
-                    BinaryOperator::Multiply => "Mul",
-                    BinaryOperator::Divide => "Div",
-                    BinaryOperator::Equals => "Eq",
-                    BinaryOperator::NotEquals => "Ne",
-                    BinaryOperator::LessThan => "Lt",
-                    BinaryOperator::GreaterThan => "Gt",
-                    BinaryOperator::LessThanOrEqual => "Le",
-                    BinaryOperator::GreaterThanOrEqual => "Ge",
-                    BinaryOperator::And => "And",
-                    BinaryOperator::Or => "Or",
-                }),
-        );
+        self.output.push_str(&prefix(&operator.method_name()));
 
         self.output.push('(');
         self.expression(right)?;
@@ -487,62 +881,73 @@ impl Transpiler {
         Ok(())
     }
 
-    fn unary_expression(&mut self, operator: &UnaryOperator, operand: Expr) -> Result<(), String> {
+    fn unary_expression(
+        &mut self,
+        operator: &UnaryOperator,
+        operand: Expr,
+    ) -> Result<(), TranspileError> {
         self.expression(operand)?;
         self.output.push('.');
 
-        self.output.push_str(
-            &(String::from("rmm__uop")
-                + match operator {
-                    UnaryOperator::Not => "Not",
-                }),
-        );
-
+        self.output.push_str(&prefix(&operator.method_name()));
         self.output.push_str("()");
         Ok(())
     }
 
-    fn function_call_expression(&mut self, callee: Expr, arguments: &[Expr]) -> Result<(), String> {
+    fn function_call_expression(
+        &mut self,
+        callee: Expr,
+        arguments: Vec<Expr>,
+        named_arguments: Vec<(String, Expr)>,
+    ) -> Result<(), TranspileError> {
         let constructor_call: Option<String> = if let Expression::MemberAccess { object, member } =
-            callee.node.clone()
-            && let Expression::Identifier(identifier) = object.node
+            &callee.node
+            && let Expression::Identifier(identifier) = &object.node
             && member == "new"
         {
-            Some(identifier)
+            Some(identifier.clone())
         } else {
             None
         };
 
-        let builtin: bool = if let Expression::MemberAccess { object, .. } = callee.node.clone()
-            && object.node == Expression::Identifier("Builtin".to_string())
-        {
-            true
-        } else {
-            false
-        };
+        let builtin: bool = matches!(
+            &callee.node,
+            Expression::MemberAccess { object, .. }
+                if object.node == Expression::Identifier("Builtin".to_string())
+        );
 
         let ident: bool = matches!(callee.node, Expression::Identifier(_));
 
-        let prefixed_name: String = if let Some(name) = &constructor_call {
-            format!("(new {}", prefix(name))
-        } else {
-            Self::expr_path(callee)?
-        };
-
         if builtin {
             self.output.push_str("CustomLang.");
         } else if ident {
             self.output.push_str("Program.");
         }
 
-        self.output.push_str(&prefixed_name);
+        if let Some(name) = &constructor_call {
+            self.output.push_str("(new ");
+            self.output.push_str(&prefix(name));
+        } else {
+            self.expression_path(callee)?;
+        }
         self.output.push('(');
 
-        for (i, argument) in arguments.iter().enumerate() {
-            self.expression(argument.clone())?;
-            if i < arguments.len() - 1 {
+        let argument_count: usize = arguments.len();
+        for (i, argument) in arguments.into_iter().enumerate() {
+            self.expression(argument)?;
+            if i < argument_count - 1 {
+                self.output.push_str(", ");
+            }
+        }
+
+        // C# supports named arguments natively, they only need the parameter prefix
+        for (i, (name, argument)) in named_arguments.into_iter().enumerate() {
+            if i > 0 || argument_count > 0 {
                 self.output.push_str(", ");
             }
+            self.output.push_str(&prefix(&name));
+            self.output.push_str(": ");
+            self.expression(argument)?;
         }
 
         self.output.push(')');
@@ -553,3 +958,113 @@ impl Transpiler {
         Ok(())
     }
 }
+
+/// Collects the names of all identifiers in the visited nodes
+#[derive(Debug, Default)]
+struct References(Vec<String>);
+
+impl Visitor for References {
+    fn visit_expression(&mut self, expression: &Expr) {
+        if let Expression::Identifier(name) = &expression.node {
+            self.0.push(name.clone());
+        }
+        walk_expression(self, expression);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod transpiler_tests {
+    use super::*;
+    use lexer::Lexer;
+    use parser::Parser;
+
+    fn parse(source: &str) -> Program {
+        Parser::parse(Lexer::tokenize(source).unwrap()).unwrap()
+    }
+
+    /// Appends the entry point the transpiler expects to the given source and transpiles it.
+    fn transpiled(source: &str) -> String {
+        Transpiler::transpile(parse(&format!(
+            "{source}\nclass Main {{ static int main() {{ return entry(); }} }}"
+        )))
+        .unwrap()
+    }
+
+    #[test]
+    fn unreachable_functions() {
+        let output: String = transpiled(
+            "int unused() { return 1; } int helper() { return 2; } \
+             int entry() { return helper(); }",
+        );
+
+        assert!(!output.contains("rmm_unused("));
+        assert!(output.contains("public static CustomLang.Types.rmm_Int rmm_helper()"));
+        assert!(output.contains("public static CustomLang.Types.rmm_Int rmm_entry()"));
+    }
+
+    #[test]
+    fn short_circuit() {
+        let output: String =
+            transpiled("int entry() { if (true && false) { return 1; } return 0; }");
+
+        assert!(output.contains(
+            "new CustomLang.Types.rmm_Bool(new CustomLang.Types.rmm_Bool(true).Inner && \
+             new CustomLang.Types.rmm_Bool(false).Inner)"
+        ));
+    }
+
+    #[test]
+    fn line_directives() {
+        let source: &str = "class Main { static int main() {\n    return 0;\n} }";
+
+        assert!(
+            !Transpiler::transpile(parse(source))
+                .unwrap()
+                .contains("#line")
+        );
+
+        let output: String =
+            Transpiler::transpile_with_line_directives(parse(source), "main.rmm").unwrap();
+        assert!(output.contains(
+            "#line 1 \"main.rmm\"\n    public static CustomLang.Types.rmm_Int rmm_main() {\n\
+             #line 2 \"main.rmm\"\n      return new CustomLang.Types.rmm_Int(0);"
+        ));
+    }
+
+    #[test]
+    fn try_statement() {
+        let output: String = transpiled(
+            "int entry() { try { throw \"failed\"; } catch (e) { Builtin.println(e); } return 0; }",
+        );
+
+        assert!(output.contains(
+            "throw new System.Exception(new CustomLang.Types.rmm_String(\"failed\").Inner);"
+        ));
+        assert!(output.contains(
+            "} catch (System.Exception __error2) {\n      \
+             CustomLang.Types.rmm_String rmm_e = new(__error2.Message);"
+        ));
+    }
+
+    #[test]
+    fn inheritance() {
+        let output: String = transpiled(
+            "class Animal { int count() { return 4; } } \
+             class Dog : Animal { int count() { return 2; } } \
+             int entry() { Animal a = Dog.new(); return a.count(); }",
+        );
+
+        assert!(output.contains("class rmm_Dog : rmm_Animal {"));
+        assert!(output.contains("public virtual CustomLang.Types.rmm_Int rmm_count()"));
+        assert!(output.contains("public override CustomLang.Types.rmm_Int rmm_count()"));
+
+        // The subclass constructor needs a parameterless base constructor to call
+        let output: String = transpiled(
+            "class Animal { int legs; static Self Animal(int legs) { self.legs = legs; } } \
+             class Dog : Animal { static Self Dog() { self.legs = 4; } } \
+             int entry() { return Dog.new().legs; }",
+        );
+        assert!(output.contains("protected rmm_Animal() {}"));
+    }
+}